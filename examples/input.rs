@@ -0,0 +1,31 @@
+use semtext::input::Action;
+use semtext::widget::{Label, TextInput};
+use semtext::{grid_area, Screen, Widget};
+use std::error::Error;
+
+async fn async_main() -> Result<(), Box<dyn Error>> {
+    let mut screen = Screen::new()?;
+    let a = Label::new("Name:");
+    let b = TextInput::new("")
+        .with_placeholder("First Last")
+        .into_border();
+    let grid = grid_area!(
+        [. . . .]
+        [. a b .]
+        [. . . .]
+    )?;
+    let name = loop {
+        match screen.step(&grid).await? {
+            Action::Quit() => return Ok(()),
+            Action::Submit(text) => break text,
+            _ => (),
+        }
+    };
+    drop(screen);
+    println!("Hello, {}!", name);
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    futures::executor::block_on(async_main())
+}
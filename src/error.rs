@@ -17,9 +17,29 @@ pub enum Error {
     /// [Glyph]: text/struct.Glyph.html
     InvalidGlyphWidth(usize),
 
+    /// A [Glyph] must not contain control characters
+    ///
+    /// [Glyph]: text/struct.Glyph.html
+    InvalidGlyphChar(char),
+
     /// Invalid grid area layout
     InvalidGridArea(),
 
+    /// Invalid [Color] name
+    ///
+    /// [Color]: text/enum.Color.html
+    InvalidColor(String),
+
+    /// Invalid [Outline] name
+    ///
+    /// [Outline]: text/enum.Outline.html
+    InvalidOutline(String),
+
+    /// Invalid [Theme] configuration
+    ///
+    /// [Theme]: text/struct.Theme.html
+    InvalidTheme(String),
+
     /// I/O error
     Io(io::Error),
 }
@@ -34,9 +54,19 @@ impl fmt::Display for Error {
             Error::InvalidGlyphWidth(w) => {
                 write!(fmt, "Invalid glyph width: {}", w)
             }
+            Error::InvalidGlyphChar(ch) => {
+                write!(fmt, "Invalid glyph char: {:?}", ch)
+            }
             Error::InvalidGridArea() => {
                 write!(fmt, "Invalid grid: all widgets must be rectangular")
             }
+            Error::InvalidColor(name) => {
+                write!(fmt, "Invalid color: \"{}\"", name)
+            }
+            Error::InvalidOutline(name) => {
+                write!(fmt, "Invalid outline: \"{}\"", name)
+            }
+            Error::InvalidTheme(msg) => write!(fmt, "Invalid theme: {}", msg),
             Error::Io(ref err) => err.fmt(fmt),
         }
     }
@@ -4,11 +4,15 @@
 //
 use crate::input::{KeyPress, ModKeys, NavKey};
 use crate::layout::Dim;
+use crate::widget::CheckState;
 use std::collections::HashMap;
 
 /// Screen actions
+///
+/// Actions are `Clone` but not `Copy`, since some variants carry owned
+/// payloads.
 #[non_exhaustive]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Action {
     /// Terminal resized
     Resize(Dim),
@@ -18,6 +22,33 @@ pub enum Action {
 
     /// Quit application
     Quit(),
+
+    /// Hyperlink clicked (link target)
+    Link(String),
+
+    /// Text input submitted (text)
+    Submit(String),
+
+    /// Check box changed (new state)
+    Checked(CheckState),
+
+    /// Toggle switch changed (new state)
+    Toggled(bool),
+
+    /// Option selected (index)
+    Selected(usize),
+
+    /// Selection changed (selected indices)
+    SelectionChanged(Vec<usize>),
+
+    /// Tree node selected (path of child indices)
+    NodeSelected(Vec<usize>),
+
+    /// Tab selected (index)
+    TabSelected(usize),
+
+    /// Tab closed (index)
+    TabClosed(usize),
 }
 
 /// Key / Action mapping
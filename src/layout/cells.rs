@@ -3,9 +3,14 @@
 // Copyright (c) 2020  Douglas P Lau
 //
 use crate::layout::{BBox, Pos};
-use crate::text::{Glyph, TextStyle, Theme};
+use crate::text::{
+    grapheme_width, reorder_str, sanitize, sanitize_lines, Align, ColorDepth,
+    Direction, Glyph, Span, StyleGroup, TextStyle, Theme,
+};
 use crate::{Result, Screen};
 use textwrap::wrap;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 /// Cells of text on a [Screen]
 ///
@@ -17,13 +22,25 @@ pub struct Cells<'a> {
     bbox: BBox,
     /// Bounding box of clip area
     clip: BBox,
+    /// Cursor position relative to clip area
+    cursor: Pos,
+    /// Style overrides (innermost last)
+    overrides: Vec<(StyleGroup, TextStyle)>,
 }
 
 impl<'a> Cells<'a> {
     /// Create cells
     pub fn new(screen: &'a mut Screen, bbox: BBox) -> Self {
         let clip = bbox;
-        Self { screen, bbox, clip }
+        let cursor = Pos::default();
+        let overrides = vec![];
+        Self {
+            screen,
+            bbox,
+            clip,
+            cursor,
+            overrides,
+        }
     }
 
     /// Get the width
@@ -49,6 +66,16 @@ impl<'a> Cells<'a> {
         }
     }
 
+    /// Narrow the cells to an inset area
+    ///
+    /// Unlike clipping, this also changes the bounding box, so a wrapped
+    /// widget can draw (and clip) within the inset as if it were the entire
+    /// area.
+    pub(crate) fn narrow(&mut self, inset: BBox) {
+        self.clip(Some(inset));
+        self.bbox = self.clip;
+    }
+
     /// Fill the cells with a glyph
     pub fn fill(&mut self, glyph: &Glyph) -> Result<()> {
         let bbox = self.clip;
@@ -58,7 +85,7 @@ impl<'a> Cells<'a> {
             for row in 0..bbox.height() {
                 self.move_to(0, row)?;
                 for _ in 0..fill_width {
-                    glyph.print(&mut self.screen)?;
+                    glyph.print(self.screen)?;
                 }
             }
         }
@@ -67,7 +94,47 @@ impl<'a> Cells<'a> {
 
     /// Get the screen theme
     pub fn theme(&self) -> &Theme {
-        &self.screen.theme()
+        self.screen.theme()
+    }
+
+    /// Get the text style for a style group
+    ///
+    /// Style overrides from enclosing widgets take precedence over the
+    /// [Theme].  With a `Monochrome` color depth, text attributes are added
+    /// to distinguish style groups.
+    pub fn style(&self, group: StyleGroup) -> TextStyle {
+        let style = self
+            .overrides
+            .iter()
+            .rev()
+            .find(|(g, _)| *g == group)
+            .map(|(_, st)| *st)
+            .unwrap_or_else(|| self.theme().style(group));
+        match self.screen.color_depth() {
+            ColorDepth::Monochrome => {
+                style.with_appearance(group.monochrome(style.appearance()))
+            }
+            _ => style,
+        }
+    }
+
+    /// Push style overrides
+    ///
+    /// Returns the previous override count, for [restore_overrides].
+    ///
+    /// [restore_overrides]: struct.Cells.html#method.restore_overrides
+    pub(crate) fn push_overrides(
+        &mut self,
+        overrides: &[(StyleGroup, TextStyle)],
+    ) -> usize {
+        let len = self.overrides.len();
+        self.overrides.extend_from_slice(overrides);
+        len
+    }
+
+    /// Restore style overrides to a previous count
+    pub(crate) fn restore_overrides(&mut self, len: usize) {
+        self.overrides.truncate(len);
     }
 
     /// Set the text style
@@ -75,28 +142,103 @@ impl<'a> Cells<'a> {
         self.screen.set_style(st)
     }
 
+    /// Set the hyperlink target for printed text
+    pub(crate) fn set_link(&mut self, link: Option<&str>) -> Result<()> {
+        self.screen.set_link(link)
+    }
+
     /// Move cursor to a cell
     pub fn move_to(&mut self, col: u16, row: u16) -> Result<()> {
-        let col = self.clip.left() + col;
-        let row = self.clip.top() + row;
+        self.cursor = Pos::new(col, row);
+        let col = self.clip.left().saturating_add(col);
+        let row = self.clip.top().saturating_add(row);
         self.screen.move_to(col, row)
     }
 
+    /// Show the text cursor at a cell
+    ///
+    /// The cursor is shown after all widgets are drawn, if the cell is within
+    /// the clip area.  Otherwise, it remains hidden.
+    pub fn show_cursor(&mut self, col: u16, row: u16) {
+        if col < self.width() && row < self.height() {
+            let col = self.clip.left() + col;
+            let row = self.clip.top() + row;
+            self.screen.set_cursor(Some(Pos::new(col, row)));
+        }
+    }
+
     /// Move cursor right by a number of columns
     pub fn move_right(&mut self, col: u16) -> Result<()> {
+        self.cursor.col = self.cursor.col.saturating_add(col);
         self.screen.move_right(col)
     }
 
     /// Print a char at the cursor location
+    ///
+    /// The char is not printed if it does not fit within the cells.
     pub fn print_char(&mut self, ch: char) -> Result<()> {
-        // FIXME: check width first
-        self.screen.print_char(ch)
+        let mut buf = [0; 4];
+        self.print_str(ch.encode_utf8(&mut buf))
     }
 
     /// Print a str at the cursor location
+    ///
+    /// Each grapheme cluster is treated as a unit, and any which do not fit
+    /// within the cells are clipped.  If a wide cluster is cut off at the
+    /// edge, its remaining cells are filled with spaces.  Tabs are expanded
+    /// using the [Theme] tab stop, and other control characters are replaced.
     pub fn print_str(&mut self, st: &str) -> Result<()> {
-        // FIXME: check width first
-        self.screen.print_str(st)
+        if self.cursor.row >= self.height() {
+            return Ok(());
+        }
+        let theme = self.theme();
+        let st =
+            sanitize(st, self.cursor.col, theme.tab_stop, theme.control_chars);
+        let st = &st[..];
+        let avail = self.width().saturating_sub(self.cursor.col);
+        let (end, width) = clip_str(st, avail);
+        if end > 0 {
+            self.screen.print_str(&st[..end])?;
+        }
+        self.cursor.col = if end < st.len() {
+            // Fill cells which a cut off wide grapheme cluster would cover
+            let gap = usize::from(avail - width);
+            if gap > 0 {
+                self.screen.print_str(&" ".repeat(gap))?;
+            }
+            // Nothing more fits on this row
+            self.width()
+        } else {
+            self.cursor.col + width
+        };
+        Ok(())
+    }
+
+    /// Print a str at the cursor location, cut off on the left
+    ///
+    /// This is for text scrolled horizontally; the first `cut` columns are
+    /// not printed.  If a wide grapheme cluster is cut off, its remaining
+    /// cells are filled with spaces.
+    pub(crate) fn print_str_cut(&mut self, st: &str, cut: u16) -> Result<()> {
+        let cut = usize::from(cut);
+        let mut col = 0;
+        for (i, grapheme) in st.grapheme_indices(true) {
+            if col >= cut {
+                self.print_str(&" ".repeat(col - cut))?;
+                return self.print_str(&st[i..]);
+            }
+            col += grapheme_width(grapheme);
+        }
+        self.print_str(&" ".repeat(col.saturating_sub(cut)))
+    }
+
+    /// Print a span of styled text at the cursor location
+    pub fn print_span(&mut self, span: &Span) -> Result<()> {
+        let style = span.apply_appearance(self.style(span.group()));
+        self.set_style(style)?;
+        self.set_link(span.link())?;
+        self.print_str(span.text())?;
+        self.set_link(None)
     }
 
     /// Print some text
@@ -112,17 +254,90 @@ impl<'a> Cells<'a> {
     /// <u>Underline</u>  | `<u>Underline</u>`
     /// `Reverse`         | `` `Reverse` ``
     pub fn print_text(&mut self, text: &str, offset: Pos) -> Result<()> {
+        self.print_text_aligned(text, offset, Align::default())
+    }
+
+    /// Print some text with an alignment
+    ///
+    /// Each line is reordered for bidirectional text, using the [Direction]
+    /// of its paragraph.  The direction is also used for `Start` and `End`
+    /// [Align]ment.
+    pub fn print_text_aligned(
+        &mut self,
+        text: &str,
+        offset: Pos,
+        align: Align,
+    ) -> Result<()> {
         assert_eq!(offset.col, 0, "FIXME");
         let top = usize::from(offset.row);
         let width = usize::from(self.width());
         let height = usize::from(self.height());
-        for (row, txt) in
-            wrap(&text, width).iter().skip(top).take(height).enumerate()
-        {
+        let theme = self.theme();
+        let text = sanitize_lines(text, theme.tab_stop, theme.control_chars);
+        let lines = text.split('\n').flat_map(|para| {
+            let dir = Direction::detect(para);
+            wrap(para, width).into_iter().map(move |line| (dir, line))
+        });
+        for (row, (dir, txt)) in lines.skip(top).take(height).enumerate() {
             let row = row as u16; // limited to u16 by take(height)
-            self.move_to(0, row)?;
+            let txt = reorder_str(&txt, dir);
+            let col = align.offset(dir, self.width(), txt.width() as u16);
+            self.move_to(col, row)?;
             self.print_str(&txt)?;
         }
         Ok(())
     }
 }
+
+/// Clip a str to a number of columns
+///
+/// * `st`: The str to clip
+/// * `avail`: Available width in text cells
+///
+/// ## Return
+///
+/// Byte offset of the end of the last grapheme cluster which fits, and its
+/// width in text cells.
+fn clip_str(st: &str, avail: u16) -> (usize, u16) {
+    let avail = usize::from(avail);
+    let mut end = 0;
+    let mut width = 0;
+    for (i, grapheme) in st.grapheme_indices(true) {
+        let w = grapheme_width(grapheme);
+        if width + w > avail {
+            break;
+        }
+        width += w;
+        end = i + grapheme.len();
+    }
+    // limited to u16 by avail
+    (end, width as u16)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clip_ascii() {
+        assert_eq!(clip_str("Hello", 8), (5, 5));
+        assert_eq!(clip_str("Hello", 3), (3, 3));
+        assert_eq!(clip_str("Hello", 0), (0, 0));
+    }
+
+    #[test]
+    fn clip_wide() {
+        assert_eq!(clip_str("🦀🦀", 4), (8, 4));
+        assert_eq!(clip_str("🦀🦀", 3), (4, 2));
+        assert_eq!(clip_str("🦀🦀", 1), (0, 0));
+    }
+
+    #[test]
+    fn clip_clusters() {
+        assert_eq!(clip_str("a\u{308}b", 1), (3, 1));
+        assert_eq!(clip_str("a\u{308}b", 2), (4, 2));
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(clip_str(family, 2), (family.len(), 2));
+        assert_eq!(clip_str(family, 1), (0, 0));
+    }
+}
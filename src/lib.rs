@@ -33,6 +33,7 @@ pub mod layout;
 mod screen;
 pub mod text;
 mod traits;
+mod watch;
 pub mod widget;
 
 pub use crate::error::Error;
@@ -2,14 +2,21 @@
 //
 // Copyright (c) 2020  Douglas P Lau
 //
-use crate::input::{Action, Event, FocusEvent, KeyMap, ModKeys, MouseEvent};
+use crate::input::{
+    Action, Event, FocusEvent, KeyMap, KeyPress, ModKeys, MouseEvent, NavKey,
+};
 use crate::layout::{BBox, Cells, Dim, GridArea, Pos};
-use crate::text::{Appearance, Color, StyleGroup, TextStyle, Theme};
-use crate::{Result, Widget};
+use crate::text::{
+    detect_extended_underline, Appearance, Color, ColorDepth, StyleGroup,
+    TextStyle, Theme,
+};
+use crate::watch::FileWatcher;
+use crate::{Error, Result, Widget};
 use crossterm::event::Event as CtEvent;
 use crossterm::{cursor, event, queue, style, terminal};
 use futures_core::stream::Stream;
 use std::io::{Stdout, Write};
+use std::path::Path;
 use std::{
     future::Future,
     pin::Pin,
@@ -30,6 +37,35 @@ impl Future for EvStreamFut {
     }
 }
 
+/// Next screen input
+enum Input {
+    /// Terminal event
+    Event(Option<crossterm::Result<CtEvent>>),
+    /// Theme file changed
+    ThemeChanged,
+}
+
+/// Future for the next screen input
+struct NextInput<'a> {
+    /// Event stream
+    ev_stream: &'a mut EvStreamFut,
+    /// Theme file watcher
+    watcher: Option<&'a FileWatcher>,
+}
+
+impl Future for NextInput<'_> {
+    type Output = Input;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Input> {
+        if let Some(watcher) = self.watcher {
+            if watcher.poll_changed(cx) {
+                return Poll::Ready(Input::ThemeChanged);
+            }
+        }
+        Pin::new(&mut *self.ev_stream).poll(cx).map(Input::Event)
+    }
+}
+
 /// Terminal screen
 pub struct Screen {
     /// Standard Output
@@ -38,8 +74,22 @@ pub struct Screen {
     dim: Dim,
     /// Style theme
     theme: Theme,
+    /// Theme file watcher
+    theme_watcher: Option<FileWatcher>,
+    /// Error from last theme file reload
+    theme_error: Option<Error>,
     /// Current text style
     style: Option<TextStyle>,
+    /// Terminal color depth
+    color_depth: ColorDepth,
+    /// Terminal supports extended underlines
+    extended_underline: bool,
+    /// Current hyperlink target
+    link: Option<String>,
+    /// Text cursor position (shown after drawing)
+    cursor: Option<Pos>,
+    /// Index of focused widget
+    focused: Option<usize>,
     /// Key / action map
     keymap: KeyMap,
     /// Event stream future.
@@ -52,7 +102,14 @@ impl Screen {
         let (width, height) = terminal::size()?;
         let dim = Dim::new(width, height);
         let theme = Theme::default();
+        let theme_watcher = None;
+        let theme_error = None;
         let style = None;
+        let color_depth = ColorDepth::detect();
+        let extended_underline = detect_extended_underline();
+        let link = None;
+        let cursor = None;
+        let focused = None;
         let keymap = KeyMap::default();
         terminal::enable_raw_mode()?;
         let mut out = std::io::stdout();
@@ -69,7 +126,14 @@ impl Screen {
             out,
             dim,
             theme,
+            theme_watcher,
+            theme_error,
             style,
+            color_depth,
+            extended_underline,
+            link,
+            cursor,
+            focused,
             keymap,
             ev_stream,
         })
@@ -87,8 +151,86 @@ impl Screen {
     }
 
     /// Set the theme
+    ///
+    /// The layout is recalculated and the screen repainted on the next
+    /// [step], since the theme can affect widget bounds.
+    ///
+    /// [step]: struct.Screen.html#method.step
     pub fn set_theme(&mut self, theme: Theme) {
         self.theme = theme;
+        self.style = None;
+    }
+
+    /// Set the theme from a [TOML] file, reloading it when changed
+    ///
+    /// While awaiting events in [step], the file is watched for changes.
+    /// When it is modified, the theme is reloaded and `step` returns
+    /// `Action::Redraw`.  If the modified file is invalid, the current theme
+    /// is kept, and the error is available from [take_theme_error].
+    ///
+    /// See [Theme::from_toml] for the file format.
+    ///
+    /// [step]: struct.Screen.html#method.step
+    /// [take_theme_error]: struct.Screen.html#method.take_theme_error
+    /// [Theme::from_toml]: text/struct.Theme.html#method.from_toml
+    /// [TOML]: https://toml.io
+    pub fn set_theme_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        self.set_theme(Theme::load(path)?);
+        self.theme_watcher = Some(FileWatcher::new(path));
+        self.theme_error = None;
+        Ok(())
+    }
+
+    /// Reload the theme file
+    ///
+    /// This can be used to reload from an [Action], such as a hotkey.  If no
+    /// theme file was set, nothing is done.
+    ///
+    /// [Action]: input/enum.Action.html
+    pub fn reload_theme(&mut self) -> Result<()> {
+        if let Some(watcher) = &self.theme_watcher {
+            let theme = Theme::load(watcher.path())?;
+            self.set_theme(theme);
+        }
+        Ok(())
+    }
+
+    /// Take the error from the last automatic theme file reload
+    pub fn take_theme_error(&mut self) -> Option<Error> {
+        self.theme_error.take()
+    }
+
+    /// Set the color depth
+    ///
+    /// This overrides the depth detected from environment variables.  Colors
+    /// are downsampled to the nearest supported by the terminal.
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.color_depth = depth;
+        self.style = None;
+    }
+
+    /// Get the color depth
+    pub fn color_depth(&self) -> ColorDepth {
+        self.color_depth
+    }
+
+    /// Enable or disable extended underlines
+    ///
+    /// This overrides terminal detection for underline styles (double,
+    /// curly, etc.) and underline colors.  When disabled, all underline
+    /// styles are drawn as single underlines.
+    pub fn set_extended_underline(&mut self, enable: bool) {
+        self.extended_underline = enable;
+        self.style = None;
+    }
+
+    /// Get a terminal color, downsampled to the color depth
+    fn term_color(&self, color: Color) -> style::Color {
+        match color.downsample(self.color_depth) {
+            Some(clr) => clr.into(),
+            None => style::Color::Reset,
+        }
     }
 
     /// Get the screen bounding box
@@ -120,7 +262,8 @@ impl Screen {
     /// Set the background color
     fn set_background_color(&mut self, color: Color) -> Result<()> {
         if self.style.map_or(true, |s| s.background() != color) {
-            queue!(self.out, style::SetBackgroundColor(color.into()))?;
+            let color = self.term_color(color);
+            queue!(self.out, style::SetBackgroundColor(color))?;
         }
         Ok(())
     }
@@ -128,18 +271,27 @@ impl Screen {
     /// Set the foreground color
     fn set_foreground_color(&mut self, color: Color) -> Result<()> {
         if self.style.map_or(true, |s| s.foreground() != color) {
-            queue!(self.out, style::SetForegroundColor(color.into()))?;
+            let color = self.term_color(color);
+            queue!(self.out, style::SetForegroundColor(color))?;
         }
         Ok(())
     }
 
     /// Set the text appearance
     fn set_appearance(&mut self, app: Appearance) -> Result<()> {
-        let attrs = app.changed(
-            self.style.map_or(Appearance::default(), |s| s.appearance()),
-        );
-        if !attrs.is_empty() {
-            queue!(self.out, style::SetAttributes(attrs))?;
+        let before =
+            self.style.map_or(Appearance::default(), |s| s.appearance());
+        let sgr = app.changed(before, self.extended_underline);
+        if !sgr.is_empty() {
+            queue!(self.out, style::Print(sgr))?;
+        }
+        let clr = app.underline_color();
+        if self.extended_underline
+            && (self.style.is_none() || clr != before.underline_color())
+        {
+            let clr = clr.and_then(|c| c.downsample(self.color_depth));
+            let sgr = clr.map_or("59".to_string(), Color::underline_sgr);
+            queue!(self.out, style::Print(format!("\x1B[{}m", sgr)))?;
         }
         Ok(())
     }
@@ -153,6 +305,32 @@ impl Screen {
         Ok(())
     }
 
+    /// Set the hyperlink target
+    ///
+    /// Links are emitted as OSC 8 sequences.  Terminals which do not support
+    /// them should ignore the sequences.
+    pub(crate) fn set_link(&mut self, link: Option<&str>) -> Result<()> {
+        if self.link.as_deref() != link {
+            // Control characters would terminate the sequence early
+            let target: String = link
+                .unwrap_or("")
+                .chars()
+                .filter(|c| !c.is_control())
+                .collect();
+            queue!(
+                self.out,
+                style::Print(format!("\x1B]8;;{}\x1B\\", target))
+            )?;
+            self.link = link.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    /// Set the text cursor position
+    pub(crate) fn set_cursor(&mut self, pos: Option<Pos>) {
+        self.cursor = pos;
+    }
+
     /// Move cursor to a cell
     pub(crate) fn move_to(&mut self, col: u16, row: u16) -> Result<()> {
         queue!(self.out, cursor::MoveTo(col, row))?;
@@ -183,13 +361,23 @@ impl Screen {
         let style = self.theme.style(StyleGroup::Enabled);
         self.set_style(style)?;
         self.clear()?;
+        self.cursor = None;
         for (widget, bbox) in widget_boxes.iter() {
             if let Some(mut cells) = self.cells(*bbox) {
-                let style = cells.theme().style(widget.style_group());
+                let style = cells.style(widget.style_group());
                 cells.set_style(style)?;
                 widget.draw(&mut cells, pos)?;
             }
         }
+        self.set_link(None)?;
+        match self.cursor {
+            Some(pos) => queue!(
+                self.out,
+                cursor::MoveTo(pos.col, pos.row),
+                cursor::Show
+            )?,
+            None => queue!(self.out, cursor::Hide)?,
+        }
         self.out.flush()?;
         Ok(())
     }
@@ -205,24 +393,58 @@ impl Screen {
                 self.dim = dim;
                 Some(Action::Resize(dim))
             }
+            Event::Key(KeyPress::Navigation(NavKey::Tab), ModKeys::Empty) => {
+                self.move_focus(widget_boxes, true)
+            }
+            Event::Key(KeyPress::Navigation(NavKey::BackTab), _) => {
+                self.move_focus(widget_boxes, false)
+            }
             Event::Key(key, mods) => {
-                // FIXME: check focused widget first
-                self.keymap.lookup(key, mods)
+                key_action(key, mods, self.focused, widget_boxes)
+                    .or_else(|| self.keymap.lookup(key, mods))
             }
             Event::Mouse(mev, mods, pos) => {
+                if let MouseEvent::ButtonDown(_) = mev {
+                    self.focused = widget_boxes
+                        .iter()
+                        .position(|(_, bbox)| bbox.within(pos).is_some());
+                }
                 mouse_action(mev, mods, pos, widget_boxes)
             }
         }
     }
 
+    /// Move focus to the next (or previous) widget which accepts it
+    fn move_focus(
+        &mut self,
+        widget_boxes: &[(&dyn Widget, BBox)],
+        forward: bool,
+    ) -> Option<Action> {
+        let (focused, action) = next_focus(self.focused, forward, widget_boxes);
+        self.focused = focused;
+        action
+    }
+
     /// Render a grid area and wait asynchronously for an action
     pub async fn step(&mut self, area: &GridArea<'_>) -> Result<Action> {
         let widget_boxes = area.widget_boxes(self.bbox(), &self.theme);
         self.draw(&widget_boxes)?;
         loop {
-            let ev = (&mut self.ev_stream).await.unwrap()?.into();
-            if let Some(action) = self.event_action(ev, &widget_boxes) {
-                return Ok(action);
+            let input = NextInput {
+                ev_stream: &mut self.ev_stream,
+                watcher: self.theme_watcher.as_ref(),
+            };
+            match input.await {
+                Input::Event(ev) => {
+                    let ev = ev.unwrap()?.into();
+                    if let Some(action) = self.event_action(ev, &widget_boxes) {
+                        return Ok(action);
+                    }
+                }
+                Input::ThemeChanged => match self.reload_theme() {
+                    Ok(()) => return Ok(Action::Redraw()),
+                    Err(err) => self.theme_error = Some(err),
+                },
             }
         }
     }
@@ -252,6 +474,53 @@ impl Drop for Screen {
     }
 }
 
+/// Handle a key action
+///
+/// The key is sent to the focused widget.
+fn key_action(
+    key: KeyPress,
+    mods: ModKeys,
+    focused: Option<usize>,
+    widget_boxes: &[(&dyn Widget, BBox)],
+) -> Option<Action> {
+    let (widget, _) = widget_boxes.get(focused?)?;
+    widget.key_event(key, mods)
+}
+
+/// Find the next (or previous) widget which accepts focus
+///
+/// Focus is taken from the focused widget, then offered to each other
+/// widget in turn, wrapping around.  A widget accepts focus if the offer
+/// produces an action, such as `Redraw`.
+///
+/// ## Return
+///
+/// Index of the newly focused widget, and its action.
+fn next_focus(
+    focused: Option<usize>,
+    forward: bool,
+    widget_boxes: &[(&dyn Widget, BBox)],
+) -> (Option<usize>, Option<Action>) {
+    let len = widget_boxes.len();
+    let focused = focused.filter(|i| *i < len);
+    let mut redraw = None;
+    if let Some(i) = focused {
+        redraw = widget_boxes[i].0.focus(FocusEvent::Take);
+    }
+    for n in 1..=len {
+        let i = match (focused, forward) {
+            (Some(f), true) => (f + n) % len,
+            (Some(f), false) => (f + len - n) % len,
+            (None, true) => n - 1,
+            (None, false) => len - n,
+        };
+        if let Some(action) = widget_boxes[i].0.focus(FocusEvent::Offer) {
+            return (Some(i), Some(action));
+        }
+    }
+    (None, redraw)
+}
+
 /// Handle a mouse action
 fn mouse_action(
     mev: MouseEvent,
@@ -281,3 +550,52 @@ fn mouse_action(
     }
     action.or(redraw)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widget::{Label, TextInput};
+
+    #[test]
+    fn focus() {
+        let label = Label::new("label");
+        let one = TextInput::new("");
+        let two = TextInput::new("");
+        let bbox = BBox::default();
+        let widget_boxes: Vec<(&dyn Widget, BBox)> =
+            vec![(&label, bbox), (&one, bbox), (&two, bbox)];
+        let key = |focused| {
+            key_action(
+                KeyPress::Character('a'),
+                ModKeys::Empty,
+                focused,
+                &widget_boxes,
+            )
+        };
+        let redraw = Some(Action::Redraw());
+        key(None);
+        assert_eq!(
+            next_focus(None, true, &widget_boxes),
+            (Some(1), redraw.clone())
+        );
+        key(Some(1));
+        key(Some(2));
+        assert_eq!(one.text(), "a");
+        assert_eq!(two.text(), "");
+        assert_eq!(
+            next_focus(Some(1), true, &widget_boxes),
+            (Some(2), redraw.clone())
+        );
+        assert_eq!(
+            next_focus(Some(2), true, &widget_boxes),
+            (Some(1), redraw.clone())
+        );
+        assert_eq!(
+            next_focus(Some(1), false, &widget_boxes),
+            (Some(2), redraw)
+        );
+        one.disable();
+        two.disable();
+        assert_eq!(next_focus(Some(2), true, &widget_boxes).0, None);
+    }
+}
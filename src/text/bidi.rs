@@ -0,0 +1,220 @@
+// bidi.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::text::{push_span, Span};
+use std::ops::Range;
+use unicode_bidi::{BidiInfo, Level};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Text direction
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// Left-to-right text
+    LeftToRight,
+    /// Right-to-left text
+    RightToLeft,
+}
+
+/// Text alignment
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Align {
+    /// Align to the start of the paragraph direction
+    ///
+    /// This is left for left-to-right text and right for right-to-left text.
+    Start,
+    /// Align to the center
+    Center,
+    /// Align to the end of the paragraph direction
+    End,
+}
+
+impl Direction {
+    /// Detect the direction of a paragraph
+    ///
+    /// This uses the first character with a strong direction, and defaults to
+    /// `LeftToRight` if there are none.
+    pub fn detect(text: &str) -> Self {
+        let info = BidiInfo::new(text, None);
+        match info.paragraphs.first() {
+            Some(para) if para.level.is_rtl() => Direction::RightToLeft,
+            _ => Direction::LeftToRight,
+        }
+    }
+
+    /// Get the paragraph embedding level
+    fn level(self) -> Level {
+        match self {
+            Direction::LeftToRight => Level::ltr(),
+            Direction::RightToLeft => Level::rtl(),
+        }
+    }
+}
+
+impl Default for Align {
+    fn default() -> Self {
+        Align::Start
+    }
+}
+
+impl Align {
+    /// Get the starting column of aligned text
+    ///
+    /// * `dir`: Paragraph direction
+    /// * `avail`: Available width in text cells
+    /// * `width`: Width of text
+    pub fn offset(self, dir: Direction, avail: u16, width: u16) -> u16 {
+        let pad = avail.saturating_sub(width);
+        match (self, dir) {
+            (Align::Center, _) => pad / 2,
+            (Align::Start, Direction::LeftToRight)
+            | (Align::End, Direction::RightToLeft) => 0,
+            _ => pad,
+        }
+    }
+}
+
+/// Get the runs of a line in visual order
+///
+/// Each run is a byte range, and a flag which is `true` for right-to-left.
+fn visual_runs(text: &str, dir: Direction) -> Vec<(Range<usize>, bool)> {
+    let info = BidiInfo::new(text, Some(dir.level()));
+    if !info.has_rtl() {
+        return vec![(0..text.len(), false)];
+    }
+    let mut runs = vec![];
+    for para in &info.paragraphs {
+        let (levels, line_runs) = info.visual_runs(para, para.range.clone());
+        for run in line_runs {
+            let rtl = levels[run.start].is_rtl();
+            runs.push((run, rtl));
+        }
+    }
+    runs
+}
+
+/// Get the mirrored form of a char (for right-to-left runs)
+fn mirror(ch: char) -> char {
+    match ch {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        _ => ch,
+    }
+}
+
+/// Reverse the grapheme clusters of right-to-left text
+fn reverse_text(text: &str) -> String {
+    let mut rev = String::with_capacity(text.len());
+    for grapheme in text.graphemes(true).rev() {
+        rev.extend(grapheme.chars().map(mirror));
+    }
+    rev
+}
+
+/// Reorder a line of text from logical to visual order
+pub(crate) fn reorder_str(text: &str, dir: Direction) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (run, rtl) in visual_runs(text, dir) {
+        if rtl {
+            out.push_str(&reverse_text(&text[run]));
+        } else {
+            out.push_str(&text[run]);
+        }
+    }
+    out
+}
+
+/// Reorder a line of spans from logical to visual order
+pub(crate) fn reorder_spans(line: &[Span], dir: Direction) -> Vec<Span> {
+    let text: String = line.iter().map(Span::text).collect();
+    let mut starts = Vec::with_capacity(line.len());
+    let mut pos = 0;
+    for span in line {
+        starts.push(pos);
+        pos += span.text().len();
+    }
+    let mut out = vec![];
+    for (run, rtl) in visual_runs(&text, dir) {
+        let mut pieces = vec![];
+        for (span, start) in line.iter().zip(&starts) {
+            let lo = run.start.max(*start);
+            let hi = run.end.min(start + span.text().len());
+            if lo < hi {
+                pieces.push((span, lo - start..hi - start));
+            }
+        }
+        if rtl {
+            pieces.reverse();
+        }
+        for (span, range) in pieces {
+            let txt = &span.text()[range];
+            if rtl {
+                push_span(&mut out, span.with_text(&reverse_text(txt)));
+            } else {
+                push_span(&mut out, span.with_text(txt));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SHALOM: &str = "\u{5E9}\u{5DC}\u{5D5}\u{5DD}";
+    const MOLAHS: &str = "\u{5DD}\u{5D5}\u{5DC}\u{5E9}";
+
+    #[test]
+    fn detect() {
+        assert_eq!(Direction::detect("abc"), Direction::LeftToRight);
+        assert_eq!(Direction::detect("123"), Direction::LeftToRight);
+        assert_eq!(Direction::detect(SHALOM), Direction::RightToLeft);
+        let mixed = format!("1 {} abc", SHALOM);
+        assert_eq!(Direction::detect(&mixed), Direction::RightToLeft);
+    }
+
+    #[test]
+    fn reorder() {
+        let ltr = Direction::LeftToRight;
+        let rtl = Direction::RightToLeft;
+        assert_eq!(reorder_str("abc", ltr), "abc");
+        assert_eq!(reorder_str(SHALOM, rtl), MOLAHS);
+        let text = format!("say {} (ok)", SHALOM);
+        assert_eq!(reorder_str(&text, ltr), format!("say {} (ok)", MOLAHS));
+        let text = format!("{} (abc)", SHALOM);
+        assert_eq!(reorder_str(&text, rtl), format!("(abc) {}", MOLAHS));
+    }
+
+    #[test]
+    fn reorder_span() {
+        use crate::text::StyleGroup;
+        let line = [
+            Span::new("ab ").with_group(StyleGroup::Primary),
+            Span::new(SHALOM),
+        ];
+        let spans = reorder_spans(&line, Direction::RightToLeft);
+        let texts: Vec<&str> = spans.iter().map(Span::text).collect();
+        assert_eq!(texts, [MOLAHS, " ab"]);
+    }
+
+    #[test]
+    fn align() {
+        let ltr = Direction::LeftToRight;
+        let rtl = Direction::RightToLeft;
+        assert_eq!(Align::Start.offset(ltr, 10, 4), 0);
+        assert_eq!(Align::Start.offset(rtl, 10, 4), 6);
+        assert_eq!(Align::End.offset(ltr, 10, 4), 6);
+        assert_eq!(Align::End.offset(rtl, 10, 4), 0);
+        assert_eq!(Align::Center.offset(rtl, 10, 4), 3);
+        assert_eq!(Align::Center.offset(ltr, 3, 4), 0);
+    }
+}
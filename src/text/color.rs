@@ -2,7 +2,31 @@
 //
 // Copyright (c) 2020  Douglas P Lau
 //
+use crate::Error;
 use crossterm::style::Color as Clr;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Terminal color depth
+///
+/// Colors which are not supported by a terminal are downsampled to the
+/// nearest supported color.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ColorDepth {
+    /// No colors (only text appearance, such as reverse)
+    ///
+    /// Style groups are distinguished by attributes instead: reverse for
+    /// focused and interacted, bold for primary, underline for hovered and
+    /// thin for disabled.
+    Monochrome,
+    /// 16 ANSI colors
+    Ansi16,
+    /// 256-color palette
+    Ansi256,
+    /// 24-bit *true color*
+    TrueColor,
+}
 
 /// Color intensity
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -15,10 +39,17 @@ pub enum Intensity {
 
 /// Text Colors
 ///
-/// Colors can be specified using one of the standard 16 ANSI colors, or as
-/// `Rgb` 24-bit *true color*.  In most cases, it is best to use the ANSI
-/// colors, since it allows the user to define their own preferences for all
-/// their terminal apps.
+/// Colors can be specified using one of the standard 16 ANSI colors, an
+/// `Indexed` 256-color palette value, or as `Rgb` 24-bit *true color*.  In
+/// most cases, it is best to use the ANSI colors, since it allows the user to
+/// define their own preferences for all their terminal apps.
+///
+/// Colors can be parsed from strings:
+/// - ANSI color names: `"cyan"` or `"bright cyan"`
+/// - 256-color palette indices: `"ansi:208"`
+/// - Hexadecimal `Rgb`: `"#ff8800"` or `"#f80"`
+/// - CSS named colors (as `Rgb`): `"orange"` or `"slategray"`.  Names which
+///   are also ANSI color names, such as `"cyan"`, are parsed as ANSI colors.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Color {
     /// ANSI color 0 *black*, and 8 *dark gray* (bright)
@@ -37,6 +68,12 @@ pub enum Color {
     Cyan(Intensity),
     /// ANSI color 7 *light gray*, and 15 *white* (bright)
     White(Intensity),
+    /// 256-color palette index
+    ///
+    /// - 0-15: ANSI colors (same as other variants)
+    /// - 16-231: 6x6x6 color cube
+    /// - 232-255: grayscale ramp
+    Indexed(u8),
     /// Red, green, blue *true color*
     Rgb(u8, u8, u8),
 }
@@ -61,7 +98,652 @@ impl From<Color> for Clr {
             Cyan(Intensity::Bright) => Clr::Cyan,
             White(Intensity::Normal) => Clr::Grey,
             White(Intensity::Bright) => Clr::White,
+            Indexed(idx) => Clr::AnsiValue(idx),
             Rgb(r, g, b) => Clr::Rgb { r, g, b },
         }
     }
 }
+
+/// Approximate RGB values for ANSI colors (xterm defaults)
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xCD, 0x00, 0x00),
+    (0x00, 0xCD, 0x00),
+    (0xCD, 0xCD, 0x00),
+    (0x00, 0x00, 0xEE),
+    (0xCD, 0x00, 0xCD),
+    (0x00, 0xCD, 0xCD),
+    (0xE5, 0xE5, 0xE5),
+    (0x7F, 0x7F, 0x7F),
+    (0xFF, 0x00, 0x00),
+    (0x00, 0xFF, 0x00),
+    (0xFF, 0xFF, 0x00),
+    (0x5C, 0x5C, 0xFF),
+    (0xFF, 0x00, 0xFF),
+    (0x00, 0xFF, 0xFF),
+    (0xFF, 0xFF, 0xFF),
+];
+
+/// Levels of 256-color palette color cube
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5F, 0x87, 0xAF, 0xD7, 0xFF];
+
+impl ColorDepth {
+    /// Detect the color depth from environment variables
+    ///
+    /// - `NO_COLOR` (non-empty): `Monochrome`
+    /// - `TERM` is `dumb`: `Monochrome`
+    /// - `COLORTERM` is `truecolor` or `24bit`: `TrueColor`
+    /// - `TERM` contains `256color`: `Ansi256`
+    /// - Otherwise: `Ansi16`
+    pub fn detect() -> Self {
+        let no_color = env::var("NO_COLOR").ok();
+        let colorterm = env::var("COLORTERM").ok();
+        let term = env::var("TERM").ok();
+        Self::from_env(
+            no_color.as_deref(),
+            colorterm.as_deref(),
+            term.as_deref(),
+        )
+    }
+
+    /// Get the color depth from environment variable values
+    fn from_env(
+        no_color: Option<&str>,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+    ) -> Self {
+        if no_color.map_or(false, |nc| !nc.is_empty()) {
+            return ColorDepth::Monochrome;
+        }
+        let term = term.unwrap_or("");
+        if term == "dumb" {
+            return ColorDepth::Monochrome;
+        }
+        match colorterm {
+            Some("truecolor") | Some("24bit") => ColorDepth::TrueColor,
+            _ if term.contains("truecolor") || term.contains("direct") => {
+                ColorDepth::TrueColor
+            }
+            _ if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+}
+
+/// Get the squared distance between two RGB colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = i32::from(a.0) - i32::from(b.0);
+    let dg = i32::from(a.1) - i32::from(b.1);
+    let db = i32::from(a.2) - i32::from(b.2);
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+/// Get the nearest color cube level index
+fn cube_index(v: u8) -> u8 {
+    CUBE_LEVELS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, lvl)| (i32::from(**lvl) - i32::from(v)).abs())
+        .map_or(0, |(i, _)| i as u8)
+}
+
+/// ANSI color names, in order
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+impl Color {
+    /// Get an ANSI color by index (0-7)
+    fn ansi(idx: usize, int: Intensity) -> Option<Self> {
+        use Color::*;
+        let ctor = [Black, Red, Green, Yellow, Blue, Magenta, Cyan, White];
+        ctor.get(idx).map(|c| c(int))
+    }
+
+    /// Get the palette index (0-15) of an ANSI color
+    fn ansi_number(self) -> Option<u8> {
+        use Color::*;
+        let (idx, int) = match self {
+            Black(int) => (0, int),
+            Red(int) => (1, int),
+            Green(int) => (2, int),
+            Yellow(int) => (3, int),
+            Blue(int) => (4, int),
+            Magenta(int) => (5, int),
+            Cyan(int) => (6, int),
+            White(int) => (7, int),
+            Indexed(_) | Rgb(_, _, _) => return None,
+        };
+        match int {
+            Intensity::Normal => Some(idx),
+            Intensity::Bright => Some(idx + 8),
+        }
+    }
+
+    /// Get an ANSI color by palette index (0-15)
+    fn ansi_index(idx: u8) -> Self {
+        let int = if idx < 8 {
+            Intensity::Normal
+        } else {
+            Intensity::Bright
+        };
+        Color::ansi(usize::from(idx % 8), int).unwrap()
+    }
+
+    /// Get the (approximate) red, green and blue components
+    ///
+    /// ANSI colors use xterm default values, since the actual values depend
+    /// on user preferences.
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        let idx = match self {
+            Color::Indexed(idx) => usize::from(idx),
+            Color::Rgb(r, g, b) => return (r, g, b),
+            _ => usize::from(self.ansi_number().unwrap_or(0)),
+        };
+        match idx {
+            0..=15 => ANSI_RGB[idx],
+            16..=231 => {
+                let i = idx - 16;
+                (
+                    CUBE_LEVELS[i / 36],
+                    CUBE_LEVELS[i / 6 % 6],
+                    CUBE_LEVELS[i % 6],
+                )
+            }
+            _ => {
+                let v = 8 + 10 * (idx - 232) as u8;
+                (v, v, v)
+            }
+        }
+    }
+
+    /// Blend with another color
+    ///
+    /// * `other`: Color to blend with
+    /// * `t`: Amount of `other`, from 0.0 to 1.0
+    ///
+    /// The result is always an `Rgb` color.
+    pub fn blend(self, other: Self, t: f32) -> Self {
+        let t = t.max(0.0).min(1.0);
+        let (r0, g0, b0) = self.to_rgb();
+        let (r1, g1, b1) = other.to_rgb();
+        let mix = |a: u8, b: u8| {
+            (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8
+        };
+        Color::Rgb(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+    }
+
+    /// Lighten the color (blend with white)
+    ///
+    /// * `amount`: From 0.0 (unchanged) to 1.0 (white)
+    pub fn lighten(self, amount: f32) -> Self {
+        self.blend(Color::Rgb(0xFF, 0xFF, 0xFF), amount)
+    }
+
+    /// Darken the color (blend with black)
+    ///
+    /// * `amount`: From 0.0 (unchanged) to 1.0 (black)
+    pub fn darken(self, amount: f32) -> Self {
+        self.blend(Color::Rgb(0, 0, 0), amount)
+    }
+
+    /// Get the relative luminance, from 0.0 (black) to 1.0 (white)
+    ///
+    /// This uses the [WCAG] definition.
+    ///
+    /// [WCAG]: https://www.w3.org/TR/WCAG21/#dfn-relative-luminance
+    pub fn luminance(self) -> f32 {
+        let linear = |c: u8| {
+            let c = f32::from(c) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = self.to_rgb();
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    /// Get the contrast ratio with another color
+    ///
+    /// This ranges from 1.0 (no contrast) to 21.0 (black and white).  [WCAG]
+    /// recommends at least 4.5 for normal text.
+    ///
+    /// [WCAG]: https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio
+    pub fn contrast_ratio(self, other: Self) -> f32 {
+        let (l0, l1) = (self.luminance(), other.luminance());
+        let (hi, lo) = if l0 > l1 { (l0, l1) } else { (l1, l0) };
+        (hi + 0.05) / (lo + 0.05)
+    }
+
+    /// Get the complementary color (hue rotated 180 degrees)
+    ///
+    /// Lightness and saturation are unchanged.  The result is always an
+    /// `Rgb` color.
+    pub fn complement(self) -> Self {
+        let (r, g, b) = self.to_rgb();
+        let sum = u16::from(r.max(g).max(b)) + u16::from(r.min(g).min(b));
+        let comp = |c: u8| (sum - u16::from(c)) as u8;
+        Color::Rgb(comp(r), comp(g), comp(b))
+    }
+
+    /// Adjust the color to contrast with a background
+    ///
+    /// The color is lightened (on dark backgrounds) or darkened (on light
+    /// backgrounds) until the contrast ratio is at least `ratio`.
+    pub fn with_contrast(self, background: Self, ratio: f32) -> Self {
+        let dark = background.luminance() < 0.18;
+        let mut clr = self;
+        for step in 1..=10 {
+            if clr.contrast_ratio(background) >= ratio {
+                break;
+            }
+            let amount = step as f32 / 10.0;
+            clr = if dark {
+                self.lighten(amount)
+            } else {
+                self.darken(amount)
+            };
+        }
+        clr
+    }
+
+    /// Get the SGR parameter for an underline color
+    pub(crate) fn underline_sgr(self) -> String {
+        match (self, self.ansi_number()) {
+            (Color::Rgb(r, g, b), _) => format!("58:2::{}:{}:{}", r, g, b),
+            (Color::Indexed(idx), _) | (_, Some(idx)) => {
+                format!("58:5:{}", idx)
+            }
+            _ => "59".to_string(),
+        }
+    }
+
+    /// Downsample a color to a color depth
+    ///
+    /// Returns the nearest color supported by the depth, or `None` for
+    /// `Monochrome`.
+    pub fn downsample(self, depth: ColorDepth) -> Option<Self> {
+        match (depth, self) {
+            (ColorDepth::Monochrome, _) => None,
+            (_, Color::Indexed(idx)) if idx < 16 => Some(match depth {
+                ColorDepth::Ansi16 => Color::ansi_index(idx),
+                _ => self,
+            }),
+            (ColorDepth::TrueColor, _)
+            | (ColorDepth::Ansi256, Color::Indexed(_))
+            | (_, Color::Black(_))
+            | (_, Color::Red(_))
+            | (_, Color::Green(_))
+            | (_, Color::Yellow(_))
+            | (_, Color::Blue(_))
+            | (_, Color::Magenta(_))
+            | (_, Color::Cyan(_))
+            | (_, Color::White(_)) => Some(self),
+            (ColorDepth::Ansi256, _) => Some(self.nearest_256()),
+            (ColorDepth::Ansi16, _) => Some(self.nearest_16()),
+        }
+    }
+
+    /// Get the nearest 256-color palette value (excluding ANSI colors)
+    fn nearest_256(self) -> Self {
+        let rgb = self.to_rgb();
+        let (r, g, b) =
+            (cube_index(rgb.0), cube_index(rgb.1), cube_index(rgb.2));
+        let cube = Color::Indexed(16 + 36 * r + 6 * g + b);
+        let avg = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+        let gray = (avg.saturating_sub(3) / 10).min(23) as u8;
+        let gray = Color::Indexed(232 + gray);
+        if distance(gray.to_rgb(), rgb) < distance(cube.to_rgb(), rgb) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    /// Get the nearest ANSI color
+    fn nearest_16(self) -> Self {
+        let rgb = self.to_rgb();
+        let idx = (0..16)
+            .min_by_key(|i| distance(ANSI_RGB[usize::from(*i)], rgb))
+            .unwrap_or(0);
+        Color::ansi_index(idx)
+    }
+}
+
+/// CSS color names (excluding ANSI color names)
+///
+/// These are all named colors from CSS Color Module Level 4, except `black`,
+/// `blue`, `cyan`, `green`, `magenta`, `red`, `white` and `yellow`.
+const CSS_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (0xF0, 0xF8, 0xFF)),
+    ("antiquewhite", (0xFA, 0xEB, 0xD7)),
+    ("aqua", (0x00, 0xFF, 0xFF)),
+    ("aquamarine", (0x7F, 0xFF, 0xD4)),
+    ("azure", (0xF0, 0xFF, 0xFF)),
+    ("beige", (0xF5, 0xF5, 0xDC)),
+    ("bisque", (0xFF, 0xE4, 0xC4)),
+    ("blanchedalmond", (0xFF, 0xEB, 0xCD)),
+    ("blueviolet", (0x8A, 0x2B, 0xE2)),
+    ("brown", (0xA5, 0x2A, 0x2A)),
+    ("burlywood", (0xDE, 0xB8, 0x87)),
+    ("cadetblue", (0x5F, 0x9E, 0xA0)),
+    ("chartreuse", (0x7F, 0xFF, 0x00)),
+    ("chocolate", (0xD2, 0x69, 0x1E)),
+    ("coral", (0xFF, 0x7F, 0x50)),
+    ("cornflowerblue", (0x64, 0x95, 0xED)),
+    ("cornsilk", (0xFF, 0xF8, 0xDC)),
+    ("crimson", (0xDC, 0x14, 0x3C)),
+    ("darkblue", (0x00, 0x00, 0x8B)),
+    ("darkcyan", (0x00, 0x8B, 0x8B)),
+    ("darkgoldenrod", (0xB8, 0x86, 0x0B)),
+    ("darkgray", (0xA9, 0xA9, 0xA9)),
+    ("darkgreen", (0x00, 0x64, 0x00)),
+    ("darkgrey", (0xA9, 0xA9, 0xA9)),
+    ("darkkhaki", (0xBD, 0xB7, 0x6B)),
+    ("darkmagenta", (0x8B, 0x00, 0x8B)),
+    ("darkolivegreen", (0x55, 0x6B, 0x2F)),
+    ("darkorange", (0xFF, 0x8C, 0x00)),
+    ("darkorchid", (0x99, 0x32, 0xCC)),
+    ("darkred", (0x8B, 0x00, 0x00)),
+    ("darksalmon", (0xE9, 0x96, 0x7A)),
+    ("darkseagreen", (0x8F, 0xBC, 0x8F)),
+    ("darkslateblue", (0x48, 0x3D, 0x8B)),
+    ("darkslategray", (0x2F, 0x4F, 0x4F)),
+    ("darkslategrey", (0x2F, 0x4F, 0x4F)),
+    ("darkturquoise", (0x00, 0xCE, 0xD1)),
+    ("darkviolet", (0x94, 0x00, 0xD3)),
+    ("deeppink", (0xFF, 0x14, 0x93)),
+    ("deepskyblue", (0x00, 0xBF, 0xFF)),
+    ("dimgray", (0x69, 0x69, 0x69)),
+    ("dimgrey", (0x69, 0x69, 0x69)),
+    ("dodgerblue", (0x1E, 0x90, 0xFF)),
+    ("firebrick", (0xB2, 0x22, 0x22)),
+    ("floralwhite", (0xFF, 0xFA, 0xF0)),
+    ("forestgreen", (0x22, 0x8B, 0x22)),
+    ("fuchsia", (0xFF, 0x00, 0xFF)),
+    ("gainsboro", (0xDC, 0xDC, 0xDC)),
+    ("ghostwhite", (0xF8, 0xF8, 0xFF)),
+    ("gold", (0xFF, 0xD7, 0x00)),
+    ("goldenrod", (0xDA, 0xA5, 0x20)),
+    ("gray", (0x80, 0x80, 0x80)),
+    ("greenyellow", (0xAD, 0xFF, 0x2F)),
+    ("grey", (0x80, 0x80, 0x80)),
+    ("honeydew", (0xF0, 0xFF, 0xF0)),
+    ("hotpink", (0xFF, 0x69, 0xB4)),
+    ("indianred", (0xCD, 0x5C, 0x5C)),
+    ("indigo", (0x4B, 0x00, 0x82)),
+    ("ivory", (0xFF, 0xFF, 0xF0)),
+    ("khaki", (0xF0, 0xE6, 0x8C)),
+    ("lavender", (0xE6, 0xE6, 0xFA)),
+    ("lavenderblush", (0xFF, 0xF0, 0xF5)),
+    ("lawngreen", (0x7C, 0xFC, 0x00)),
+    ("lemonchiffon", (0xFF, 0xFA, 0xCD)),
+    ("lightblue", (0xAD, 0xD8, 0xE6)),
+    ("lightcoral", (0xF0, 0x80, 0x80)),
+    ("lightcyan", (0xE0, 0xFF, 0xFF)),
+    ("lightgoldenrodyellow", (0xFA, 0xFA, 0xD2)),
+    ("lightgray", (0xD3, 0xD3, 0xD3)),
+    ("lightgreen", (0x90, 0xEE, 0x90)),
+    ("lightgrey", (0xD3, 0xD3, 0xD3)),
+    ("lightpink", (0xFF, 0xB6, 0xC1)),
+    ("lightsalmon", (0xFF, 0xA0, 0x7A)),
+    ("lightseagreen", (0x20, 0xB2, 0xAA)),
+    ("lightskyblue", (0x87, 0xCE, 0xFA)),
+    ("lightslategray", (0x77, 0x88, 0x99)),
+    ("lightslategrey", (0x77, 0x88, 0x99)),
+    ("lightsteelblue", (0xB0, 0xC4, 0xDE)),
+    ("lightyellow", (0xFF, 0xFF, 0xE0)),
+    ("lime", (0x00, 0xFF, 0x00)),
+    ("limegreen", (0x32, 0xCD, 0x32)),
+    ("linen", (0xFA, 0xF0, 0xE6)),
+    ("maroon", (0x80, 0x00, 0x00)),
+    ("mediumaquamarine", (0x66, 0xCD, 0xAA)),
+    ("mediumblue", (0x00, 0x00, 0xCD)),
+    ("mediumorchid", (0xBA, 0x55, 0xD3)),
+    ("mediumpurple", (0x93, 0x70, 0xDB)),
+    ("mediumseagreen", (0x3C, 0xB3, 0x71)),
+    ("mediumslateblue", (0x7B, 0x68, 0xEE)),
+    ("mediumspringgreen", (0x00, 0xFA, 0x9A)),
+    ("mediumturquoise", (0x48, 0xD1, 0xCC)),
+    ("mediumvioletred", (0xC7, 0x15, 0x85)),
+    ("midnightblue", (0x19, 0x19, 0x70)),
+    ("mintcream", (0xF5, 0xFF, 0xFA)),
+    ("mistyrose", (0xFF, 0xE4, 0xE1)),
+    ("moccasin", (0xFF, 0xE4, 0xB5)),
+    ("navajowhite", (0xFF, 0xDE, 0xAD)),
+    ("navy", (0x00, 0x00, 0x80)),
+    ("oldlace", (0xFD, 0xF5, 0xE6)),
+    ("olive", (0x80, 0x80, 0x00)),
+    ("olivedrab", (0x6B, 0x8E, 0x23)),
+    ("orange", (0xFF, 0xA5, 0x00)),
+    ("orangered", (0xFF, 0x45, 0x00)),
+    ("orchid", (0xDA, 0x70, 0xD6)),
+    ("palegoldenrod", (0xEE, 0xE8, 0xAA)),
+    ("palegreen", (0x98, 0xFB, 0x98)),
+    ("paleturquoise", (0xAF, 0xEE, 0xEE)),
+    ("palevioletred", (0xDB, 0x70, 0x93)),
+    ("papayawhip", (0xFF, 0xEF, 0xD5)),
+    ("peachpuff", (0xFF, 0xDA, 0xB9)),
+    ("peru", (0xCD, 0x85, 0x3F)),
+    ("pink", (0xFF, 0xC0, 0xCB)),
+    ("plum", (0xDD, 0xA0, 0xDD)),
+    ("powderblue", (0xB0, 0xE0, 0xE6)),
+    ("purple", (0x80, 0x00, 0x80)),
+    ("rebeccapurple", (0x66, 0x33, 0x99)),
+    ("rosybrown", (0xBC, 0x8F, 0x8F)),
+    ("royalblue", (0x41, 0x69, 0xE1)),
+    ("saddlebrown", (0x8B, 0x45, 0x13)),
+    ("salmon", (0xFA, 0x80, 0x72)),
+    ("sandybrown", (0xF4, 0xA4, 0x60)),
+    ("seagreen", (0x2E, 0x8B, 0x57)),
+    ("seashell", (0xFF, 0xF5, 0xEE)),
+    ("sienna", (0xA0, 0x52, 0x2D)),
+    ("silver", (0xC0, 0xC0, 0xC0)),
+    ("skyblue", (0x87, 0xCE, 0xEB)),
+    ("slateblue", (0x6A, 0x5A, 0xCD)),
+    ("slategray", (0x70, 0x80, 0x90)),
+    ("slategrey", (0x70, 0x80, 0x90)),
+    ("snow", (0xFF, 0xFA, 0xFA)),
+    ("springgreen", (0x00, 0xFF, 0x7F)),
+    ("steelblue", (0x46, 0x82, 0xB4)),
+    ("tan", (0xD2, 0xB4, 0x8C)),
+    ("teal", (0x00, 0x80, 0x80)),
+    ("thistle", (0xD8, 0xBF, 0xD8)),
+    ("tomato", (0xFF, 0x63, 0x47)),
+    ("turquoise", (0x40, 0xE0, 0xD0)),
+    ("violet", (0xEE, 0x82, 0xEE)),
+    ("wheat", (0xF5, 0xDE, 0xB3)),
+    ("whitesmoke", (0xF5, 0xF5, 0xF5)),
+    ("yellowgreen", (0x9A, 0xCD, 0x32)),
+];
+
+/// Parse an `Rgb` color in `#rrggbb` or `#rgb` form
+fn parse_rgb(hex: &str) -> Option<Color> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => {
+            let r = u8::from_str_radix(&hex[0..1], 16).ok()? * 0x11;
+            let g = u8::from_str_radix(&hex[1..2], 16).ok()? * 0x11;
+            let b = u8::from_str_radix(&hex[2..3], 16).ok()? * 0x11;
+            Some(Color::Rgb(r, g, b))
+        }
+        6 => {
+            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+            Some(Color::Rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a named color
+fn parse_name(name: &str) -> Option<Color> {
+    let (name, int) = match name.strip_prefix("bright ") {
+        Some(name) => (name.trim_start(), Intensity::Bright),
+        None => (name, Intensity::Normal),
+    };
+    if let Some(idx) = COLOR_NAMES.iter().position(|n| *n == name) {
+        return Color::ansi(idx, int);
+    }
+    match int {
+        Intensity::Normal => CSS_COLORS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, (r, g, b))| Color::Rgb(*r, *g, *b)),
+        Intensity::Bright => None,
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        let clr = if let Some(hex) = name.strip_prefix('#') {
+            parse_rgb(hex)
+        } else if let Some(idx) = name.strip_prefix("ansi:") {
+            idx.trim().parse().ok().map(Color::Indexed)
+        } else {
+            parse_name(&name)
+        };
+        clr.ok_or_else(|| Error::InvalidColor(s.to_string()))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match (*self, self.ansi_number()) {
+            (_, Some(n)) if n < 8 => {
+                write!(fmt, "{}", COLOR_NAMES[usize::from(n)])
+            }
+            (_, Some(n)) => {
+                write!(fmt, "bright {}", COLOR_NAMES[usize::from(n - 8)])
+            }
+            (Color::Indexed(idx), _) => write!(fmt, "ansi:{}", idx),
+            (Color::Rgb(r, g, b), _) => {
+                write!(fmt, "#{:02x}{:02x}{:02x}", r, g, b)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let cyan = Color::Cyan(Intensity::Normal);
+        assert_eq!("cyan".parse::<Color>().unwrap(), cyan);
+        assert_eq!(" Cyan ".parse::<Color>().unwrap(), cyan);
+        let white = Color::White(Intensity::Bright);
+        assert_eq!("bright white".parse::<Color>().unwrap(), white);
+        let rgb = Color::Rgb(0xFF, 0x88, 0x00);
+        assert_eq!("#FF8800".parse::<Color>().unwrap(), rgb);
+        assert!("#ff880".parse::<Color>().is_err());
+        assert!("bright".parse::<Color>().is_err());
+        assert_eq!("#f80".parse::<Color>().unwrap(), rgb);
+        assert!("blurple".parse::<Color>().is_err());
+        let orange = Color::Rgb(0xFF, 0xA5, 0x00);
+        assert_eq!("Orange".parse::<Color>().unwrap(), orange);
+        assert!("bright orange".parse::<Color>().is_err());
+        let purple = Color::Rgb(0x66, 0x33, 0x99);
+        assert_eq!("RebeccaPurple".parse::<Color>().unwrap(), purple);
+        let lgy = Color::Rgb(0xFA, 0xFA, 0xD2);
+        assert_eq!("lightgoldenrodyellow".parse::<Color>().unwrap(), lgy);
+        assert_eq!(CSS_COLORS.len(), 140);
+        for (name, _) in CSS_COLORS {
+            assert!(!COLOR_NAMES.contains(name));
+        }
+        assert_eq!("ansi:208".parse::<Color>().unwrap(), Color::Indexed(208));
+        assert!("ansi:256".parse::<Color>().is_err());
+        assert!("ansi:".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn depth() {
+        use ColorDepth::*;
+        let xterm = Some("xterm-256color");
+        assert_eq!(ColorDepth::from_env(Some("1"), None, xterm), Monochrome);
+        assert_eq!(ColorDepth::from_env(Some(""), None, xterm), Ansi256);
+        assert_eq!(ColorDepth::from_env(None, None, Some("dumb")), Monochrome);
+        let tc = Some("truecolor");
+        assert_eq!(ColorDepth::from_env(None, tc, xterm), TrueColor);
+        assert_eq!(ColorDepth::from_env(None, None, Some("xterm")), Ansi16);
+        assert_eq!(ColorDepth::from_env(None, None, None), Ansi16);
+    }
+
+    #[test]
+    fn downsample() {
+        let orange = Color::Rgb(0xFF, 0x88, 0x00);
+        assert_eq!(orange.downsample(ColorDepth::TrueColor), Some(orange));
+        let c256 = Some(Color::Indexed(208));
+        assert_eq!(orange.downsample(ColorDepth::Ansi256), c256);
+        let gray = Color::Rgb(0x80, 0x80, 0x80);
+        let c256 = Some(Color::Indexed(244));
+        assert_eq!(gray.downsample(ColorDepth::Ansi256), c256);
+        let red = Some(Color::Red(Intensity::Bright));
+        assert_eq!(
+            Color::Rgb(0xF0, 0x10, 0x10).downsample(ColorDepth::Ansi16),
+            red
+        );
+        assert_eq!(Color::Indexed(9).downsample(ColorDepth::Ansi16), red);
+        let blue = Color::Blue(Intensity::Normal);
+        assert_eq!(blue.downsample(ColorDepth::Ansi16), Some(blue));
+        assert_eq!(blue.downsample(ColorDepth::Monochrome), None);
+        assert_eq!(Color::Indexed(196).to_rgb(), (0xFF, 0, 0));
+        assert_eq!(Color::Indexed(232).to_rgb(), (8, 8, 8));
+        assert_eq!(
+            Color::White(Intensity::Bright).to_rgb(),
+            (0xFF, 0xFF, 0xFF)
+        );
+    }
+
+    #[test]
+    fn blend() {
+        let black = Color::Black(Intensity::Normal);
+        let white = Color::Rgb(0xFF, 0xFF, 0xFF);
+        assert_eq!(black.blend(white, 0.5), Color::Rgb(128, 128, 128));
+        assert_eq!(black.blend(white, 2.0), white);
+        let red = Color::Rgb(200, 0, 0);
+        assert_eq!(red.lighten(0.5), Color::Rgb(228, 128, 128));
+        assert_eq!(red.darken(0.5), Color::Rgb(100, 0, 0));
+    }
+
+    #[test]
+    fn contrast() {
+        let black = Color::Rgb(0, 0, 0);
+        let white = Color::Rgb(0xFF, 0xFF, 0xFF);
+        assert!((black.contrast_ratio(white) - 21.0).abs() < 0.01);
+        assert!((white.contrast_ratio(white) - 1.0).abs() < 0.01);
+        let gray = Color::Rgb(0x80, 0x80, 0x80);
+        assert!(gray.with_contrast(black, 7.0).contrast_ratio(black) >= 7.0);
+        assert!(gray.with_contrast(white, 7.0).contrast_ratio(white) >= 7.0);
+    }
+
+    #[test]
+    fn complement() {
+        let orange = Color::Rgb(0xFF, 0x88, 0x00);
+        assert_eq!(orange.complement(), Color::Rgb(0x00, 0x77, 0xFF));
+        assert_eq!(orange.complement().complement(), orange);
+        let gray = Color::Rgb(0x80, 0x80, 0x80);
+        assert_eq!(gray.complement(), gray);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Color::Blue(Intensity::Normal).to_string(), "blue");
+        let black = Color::Black(Intensity::Bright);
+        assert_eq!(black.to_string(), "bright black");
+        assert_eq!(Color::Rgb(1, 35, 255).to_string(), "#0123ff");
+        assert_eq!(Color::Indexed(208).to_string(), "ansi:208");
+        let clr: Clr = Color::Indexed(208).into();
+        assert_eq!(clr, Clr::AnsiValue(208));
+    }
+}
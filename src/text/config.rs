@@ -0,0 +1,353 @@
+// config.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::text::{Color, ControlChars, Outline, Theme};
+use crate::widget::BorderStyle;
+use crate::{Error, Result};
+use std::fs;
+use std::path::Path;
+use toml::value::{Table, Value};
+
+/// Make an invalid theme error for a key
+fn invalid(key: &str, msg: impl std::fmt::Display) -> Error {
+    Error::InvalidTheme(format!("{} ({})", key, msg))
+}
+
+/// Get a string value
+fn string_value<'a>(key: &str, value: &'a Value) -> Result<&'a str> {
+    value.as_str().ok_or_else(|| {
+        invalid(key, format!("expected string, found {}", value.type_str()))
+    })
+}
+
+/// Parse a color value
+fn parse_color(key: &str, value: &Value) -> Result<Color> {
+    string_value(key, value)?
+        .parse()
+        .map_err(|e| invalid(key, e))
+}
+
+/// Parse an outline value
+fn parse_outline(key: &str, value: &Value) -> Result<Outline> {
+    string_value(key, value)?
+        .parse()
+        .map_err(|e| invalid(key, e))
+}
+
+/// Parse a border style table
+fn parse_border(key: &str, value: &Value) -> Result<BorderStyle> {
+    let table = value.as_table().ok_or_else(|| {
+        invalid(key, format!("expected table, found {}", value.type_str()))
+    })?;
+    let mut style = None;
+    let mut outline = Outline::default();
+    let mut edges = [None; 4];
+    for (k, v) in table {
+        let key = format!("{}.{}", key, k);
+        match k.as_str() {
+            "style" => style = Some(string_value(&key, v)?),
+            "outline" => outline = parse_outline(&key, v)?,
+            "left" => edges[0] = Some(parse_outline(&key, v)?),
+            "top" => edges[1] = Some(parse_outline(&key, v)?),
+            "right" => edges[2] = Some(parse_outline(&key, v)?),
+            "bottom" => edges[3] = Some(parse_outline(&key, v)?),
+            _ => return Err(invalid(&key, "unknown key")),
+        }
+    }
+    let custom = edges.iter().any(Option::is_some);
+    match style {
+        Some("simple") if !custom => Ok(BorderStyle::Simple(outline)),
+        Some("bevel") if !custom => Ok(BorderStyle::Bevel(outline)),
+        Some("shadow") if !custom => Ok(BorderStyle::Shadow(outline)),
+        Some("custom") if !table.contains_key("outline") => {
+            let [left, top, right, bottom] = edges;
+            Ok(BorderStyle::Custom(left, top, right, bottom))
+        }
+        Some("simple") | Some("bevel") | Some("shadow") => Err(invalid(
+            key,
+            "edge outlines are only allowed for custom style",
+        )),
+        Some("custom") => {
+            Err(invalid(key, "outline is not allowed for custom style"))
+        }
+        Some(s) => Err(invalid(key, format!("unknown border style \"{}\"", s))),
+        None => Err(invalid(key, "missing style")),
+    }
+}
+
+/// Parse a tab stop value
+fn parse_tab_stop(key: &str, value: &Value) -> Result<u16> {
+    match value.as_integer() {
+        Some(t) if t >= 1 && t <= i64::from(u16::MAX) => Ok(t as u16),
+        Some(t) => Err(invalid(key, format!("out of range: {}", t))),
+        None => Err(invalid(
+            key,
+            format!("expected integer, found {}", value.type_str()),
+        )),
+    }
+}
+
+/// Parse a control character replacement value
+fn parse_control_chars(key: &str, value: &Value) -> Result<ControlChars> {
+    match string_value(key, value)? {
+        "caret" => Ok(ControlChars::Caret),
+        "replacement" => Ok(ControlChars::Replacement),
+        s => Err(invalid(key, format!("unknown value \"{}\"", s))),
+    }
+}
+
+/// Make a border style table
+fn border_table(bdr: BorderStyle) -> Value {
+    let mut table = Table::new();
+    let (style, outline) = match bdr {
+        BorderStyle::Simple(outline) => ("simple", Some(outline)),
+        BorderStyle::Bevel(outline) => ("bevel", Some(outline)),
+        BorderStyle::Shadow(outline) => ("shadow", Some(outline)),
+        BorderStyle::Custom(left, top, right, bottom) => {
+            let edges = [("left", left), ("top", top), ("right", right)];
+            for (k, edge) in edges.iter().chain(&[("bottom", bottom)]) {
+                if let Some(edge) = edge {
+                    table
+                        .insert(k.to_string(), Value::String(edge.to_string()));
+                }
+            }
+            ("custom", None)
+        }
+    };
+    table.insert("style".to_string(), Value::String(style.to_string()));
+    if let Some(outline) = outline {
+        table.insert("outline".to_string(), Value::String(outline.to_string()));
+    }
+    Value::Table(table)
+}
+
+impl Theme {
+    /// Parse a theme from [TOML]
+    ///
+    /// Any keys which are not present are taken from the default theme.
+    /// Unknown keys, or invalid values, are reported as
+    /// [Error::InvalidTheme].
+    ///
+    /// ```toml
+    /// # Colors: black, red, green, yellow, blue, magenta, cyan, white,
+    /// #         "bright" variants, "ansi:0" to "ansi:255", "#rrggbb" or
+    /// #         CSS color names
+    /// background = "blue"
+    /// foreground = "bright white"
+    /// primary = "yellow"
+    /// focused = "bright cyan"
+    /// interacting = "bright yellow"
+    /// dark_shadow = "bright black"
+    /// light_shadow = "white"
+    /// keyword = "bright yellow"
+    /// type_name = "bright cyan"
+    /// name = "bright green"
+    /// string = "bright magenta"
+    /// number = "bright red"
+    /// comment = "white"
+    /// error = "bright red"
+    /// warning = "bright yellow"
+    /// success = "bright green"
+    /// info = "bright cyan"
+    /// muted = "white"
+    /// secondary = "cyan"
+    /// tab_stop = 8
+    /// # Control characters: caret or replacement
+    /// control_chars = "caret"
+    ///
+    /// # Border styles: simple, bevel, shadow or custom
+    /// # Outlines: empty, light, light dashed, light rounded,
+    /// #           light dashed rounded, heavy, heavy dashed, double, tight,
+    /// #           half inner, half outer, block or medium shade
+    /// [normal_border]
+    /// style = "simple"
+    /// outline = "light"
+    ///
+    /// # Custom borders have optional left, top, right and bottom outlines
+    /// [button_border]
+    /// style = "custom"
+    /// left = "tight"
+    /// right = "tight"
+    /// ```
+    ///
+    /// [Error::InvalidTheme]: ../enum.Error.html#variant.InvalidTheme
+    /// [TOML]: https://toml.io
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let table: Table = toml::from_str(toml)
+            .map_err(|e| Error::InvalidTheme(e.to_string()))?;
+        let mut theme = Theme::default();
+        for (key, value) in &table {
+            let key = key.as_str();
+            match key {
+                "background" => theme.background = parse_color(key, value)?,
+                "foreground" => theme.foreground = parse_color(key, value)?,
+                "primary" => theme.primary = parse_color(key, value)?,
+                "focused" => theme.focused = parse_color(key, value)?,
+                "interacting" => theme.interacting = parse_color(key, value)?,
+                "dark_shadow" => theme.dark_shadow = parse_color(key, value)?,
+                "light_shadow" => theme.light_shadow = parse_color(key, value)?,
+                "keyword" => theme.keyword = parse_color(key, value)?,
+                "type_name" => theme.type_name = parse_color(key, value)?,
+                "name" => theme.name = parse_color(key, value)?,
+                "string" => theme.string = parse_color(key, value)?,
+                "number" => theme.number = parse_color(key, value)?,
+                "comment" => theme.comment = parse_color(key, value)?,
+                "error" => theme.error = parse_color(key, value)?,
+                "warning" => theme.warning = parse_color(key, value)?,
+                "success" => theme.success = parse_color(key, value)?,
+                "info" => theme.info = parse_color(key, value)?,
+                "muted" => theme.muted = parse_color(key, value)?,
+                "secondary" => theme.secondary = parse_color(key, value)?,
+                "normal_border" => {
+                    theme.normal_border = parse_border(key, value)?
+                }
+                "button_border" => {
+                    theme.button_border = parse_border(key, value)?
+                }
+                "tab_stop" => theme.tab_stop = parse_tab_stop(key, value)?,
+                "control_chars" => {
+                    theme.control_chars = parse_control_chars(key, value)?
+                }
+                _ => return Err(invalid(key, "unknown key")),
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Format the theme as [TOML]
+    ///
+    /// All keys are included, in the format accepted by [from_toml].
+    ///
+    /// [from_toml]: struct.Theme.html#method.from_toml
+    /// [TOML]: https://toml.io
+    pub fn to_toml(&self) -> String {
+        let colors = [
+            ("background", self.background),
+            ("foreground", self.foreground),
+            ("primary", self.primary),
+            ("focused", self.focused),
+            ("interacting", self.interacting),
+            ("dark_shadow", self.dark_shadow),
+            ("light_shadow", self.light_shadow),
+            ("keyword", self.keyword),
+            ("type_name", self.type_name),
+            ("name", self.name),
+            ("string", self.string),
+            ("number", self.number),
+            ("comment", self.comment),
+            ("error", self.error),
+            ("warning", self.warning),
+            ("success", self.success),
+            ("info", self.info),
+            ("muted", self.muted),
+            ("secondary", self.secondary),
+        ];
+        let mut table = Table::new();
+        for (key, clr) in &colors {
+            table.insert(key.to_string(), Value::String(clr.to_string()));
+        }
+        let ctrl = match self.control_chars {
+            ControlChars::Caret => "caret",
+            ControlChars::Replacement => "replacement",
+        };
+        table.insert(
+            "tab_stop".to_string(),
+            Value::Integer(self.tab_stop.into()),
+        );
+        table.insert(
+            "control_chars".to_string(),
+            Value::String(ctrl.to_string()),
+        );
+        table.insert(
+            "normal_border".to_string(),
+            border_table(self.normal_border),
+        );
+        table.insert(
+            "button_border".to_string(),
+            border_table(self.button_border),
+        );
+        Value::Table(table).to_string()
+    }
+
+    /// Load a theme from a [TOML] file
+    ///
+    /// See [from_toml] for the file format.
+    ///
+    /// [from_toml]: struct.Theme.html#method.from_toml
+    /// [TOML]: https://toml.io
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Theme::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Save the theme to a [TOML] file
+    ///
+    /// [TOML]: https://toml.io
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(fs::write(path, self.to_toml())?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::{Corner, Intensity, Stroke};
+
+    #[test]
+    fn round_trip() {
+        let theme = Theme::default();
+        assert_eq!(Theme::from_toml(&theme.to_toml()).unwrap(), theme);
+        let mut theme = Theme::default()
+            .with_background(Color::Rgb(0x10, 0x20, 0x30))
+            .with_tab_stop(4)
+            .with_control_chars(ControlChars::Replacement);
+        theme.button_border = BorderStyle::Custom(
+            Some(Outline::Tight),
+            None,
+            Some(Outline::Light(Stroke::Dashed, Corner::Rounded)),
+            None,
+        );
+        assert_eq!(Theme::from_toml(&theme.to_toml()).unwrap(), theme);
+    }
+
+    #[test]
+    fn partial() {
+        let toml = r#"
+            background = "black"
+            [normal_border]
+            style = "shadow"
+            outline = "double"
+        "#;
+        let theme = Theme::from_toml(toml).unwrap();
+        assert_eq!(theme.background, Color::Black(Intensity::Normal));
+        assert_eq!(theme.normal_border, BorderStyle::Shadow(Outline::Double));
+        assert_eq!(theme.foreground, Theme::default().foreground);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = |toml| Theme::from_toml(toml).unwrap_err().to_string();
+        assert_eq!(
+            err("background = \"blurple\""),
+            "Invalid theme: background (Invalid color: \"blurple\")"
+        );
+        assert_eq!(
+            err("colour = \"red\""),
+            "Invalid theme: colour (unknown key)"
+        );
+        assert_eq!(
+            err("tab_stop = 0"),
+            "Invalid theme: tab_stop (out of range: 0)"
+        );
+        assert_eq!(
+            err("[normal_border]\nstyle = \"simple\"\noutline = \"thin\""),
+            "Invalid theme: normal_border.outline (Invalid outline: \"thin\")"
+        );
+        assert_eq!(
+            err("[button_border]\nstyle = \"bevel\"\ntop = \"light\""),
+            "Invalid theme: button_border (edge outlines are only allowed for \
+             custom style)"
+        );
+        assert!(err("background = ").starts_with("Invalid theme: "));
+    }
+}
@@ -0,0 +1,139 @@
+// control.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::text::grapheme_width;
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Control character replacement
+///
+/// Control characters (other than tab) are never sent to the terminal
+/// directly, since they can corrupt the screen layout.  Instead, they are
+/// replaced with visible text.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ControlChars {
+    /// Caret notation, such as `^M` for carriage return
+    ///
+    /// C1 control characters use the replacement character `�`
+    Caret,
+    /// Replacement character `�` (U+FFFD)
+    Replacement,
+}
+
+impl Default for ControlChars {
+    fn default() -> Self {
+        ControlChars::Caret
+    }
+}
+
+impl ControlChars {
+    /// Push the replacement for a control character
+    fn push_replacement(self, ch: char, out: &mut String) {
+        match (self, u32::from(ch)) {
+            (ControlChars::Caret, c @ 0x00..=0x1F) => {
+                out.push('^');
+                out.push(char::from(c as u8 + b'@'));
+            }
+            (ControlChars::Caret, 0x7F) => out.push_str("^?"),
+            _ => out.push('\u{FFFD}'),
+        }
+    }
+}
+
+/// Sanitize text for printing
+///
+/// Tabs are expanded to spaces, and other control characters are replaced.
+///
+/// * `text`: Text to sanitize
+/// * `col`: Starting column, used for tab stops
+/// * `tab_stop`: Number of columns between tab stops
+/// * `ctrl`: Control character replacement
+pub(crate) fn sanitize(
+    text: &str,
+    col: u16,
+    tab_stop: u16,
+    ctrl: ControlChars,
+) -> Cow<'_, str> {
+    if !text.chars().any(char::is_control) {
+        return Cow::Borrowed(text);
+    }
+    let tab_stop = usize::from(tab_stop.max(1));
+    let mut col = usize::from(col);
+    let mut out = String::with_capacity(text.len());
+    for grapheme in text.graphemes(true) {
+        if grapheme == "\t" {
+            let spaces = tab_stop - col % tab_stop;
+            out.extend(std::iter::repeat(' ').take(spaces));
+            col += spaces;
+        } else if grapheme.chars().any(char::is_control) {
+            let start = out.len();
+            for ch in grapheme.chars() {
+                if ch.is_control() {
+                    ctrl.push_replacement(ch, &mut out);
+                } else {
+                    out.push(ch);
+                }
+            }
+            col += out[start..].width();
+        } else {
+            out.push_str(grapheme);
+            col += grapheme_width(grapheme);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Sanitize lines of text for printing
+///
+/// Each line (separated by `\n`) is sanitized, starting at column 0.
+pub(crate) fn sanitize_lines(
+    text: &str,
+    tab_stop: u16,
+    ctrl: ControlChars,
+) -> Cow<'_, str> {
+    if !text.chars().any(|c| c != '\n' && c.is_control()) {
+        return Cow::Borrowed(text);
+    }
+    let lines: Vec<_> = text
+        .split('\n')
+        .map(|line| sanitize(line, 0, tab_stop, ctrl))
+        .collect();
+    Cow::Owned(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tabs() {
+        let ctrl = ControlChars::Caret;
+        assert_eq!(sanitize("a\tb", 0, 4, ctrl), "a   b");
+        assert_eq!(sanitize("a\tb", 2, 4, ctrl), "a b");
+        assert_eq!(sanitize("\t\tb", 0, 4, ctrl), "        b");
+        assert_eq!(sanitize("🦀\tb", 0, 4, ctrl), "🦀  b");
+        assert_eq!(sanitize("a\tb", 0, 0, ctrl), "a b");
+    }
+
+    #[test]
+    fn controls() {
+        let caret = ControlChars::Caret;
+        let repl = ControlChars::Replacement;
+        assert_eq!(sanitize("plain", 0, 8, caret), "plain");
+        assert_eq!(sanitize("line\r\n", 0, 8, caret), "line^M^J");
+        assert_eq!(sanitize("\x1b[2J", 0, 8, caret), "^[[2J");
+        assert_eq!(sanitize("del\x7f", 0, 8, caret), "del^?");
+        assert_eq!(sanitize("c1\u{85}", 0, 8, caret), "c1\u{FFFD}");
+        assert_eq!(sanitize("bell\x07", 0, 8, repl), "bell\u{FFFD}");
+        assert_eq!(sanitize("^M\r\tx", 0, 8, caret), "^M^M    x");
+    }
+
+    #[test]
+    fn lines() {
+        let ctrl = ControlChars::Caret;
+        assert_eq!(sanitize_lines("a\nb", 4, ctrl), "a\nb");
+        assert_eq!(sanitize_lines("a\tb\n\tc", 4, ctrl), "a   b\n    c");
+    }
+}
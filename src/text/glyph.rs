@@ -3,6 +3,7 @@
 // Copyright (c) 2020  Douglas P Lau
 //
 use crate::{Error, Result, Screen};
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Inner enum for glyphs
@@ -57,6 +58,9 @@ impl IntoGlyph for char {
 impl IntoGlyph for &str {
     /// Create a Glyphn from a `&str`
     fn into_glyph(self) -> Result<Glyph> {
+        if let Some(ch) = self.chars().find(|c| c.is_control()) {
+            return Err(Error::InvalidGlyphChar(ch));
+        }
         let width = self.width();
         if width == 1 || width == 2 {
             let inner = GlyphInner::Str(self.to_string());
@@ -66,6 +70,33 @@ impl IntoGlyph for &str {
     }
 }
 
+/// Get the width of a grapheme cluster in text cells
+///
+/// Clusters made from multiple wide characters (such as emoji ZWJ sequences)
+/// are limited to 2 cells, the same as a [Glyph].
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+/// Fit a str within a number of columns
+///
+/// Returns the longest prefix of whole grapheme clusters which fits, and its
+/// width in text cells.
+pub(crate) fn fit_str(st: &str, avail: u16) -> (&str, u16) {
+    let avail = usize::from(avail);
+    let mut end = 0;
+    let mut width = 0;
+    for (i, grapheme) in st.grapheme_indices(true) {
+        let w = grapheme_width(grapheme);
+        if width + w > avail {
+            break;
+        }
+        width += w;
+        end = i + grapheme.len();
+    }
+    (&st[..end], width as u16)
+}
+
 impl Glyph {
     /// Get the glyph width.
     ///
@@ -78,8 +109,21 @@ impl Glyph {
     pub fn print(&self, screen: &mut Screen) -> Result<()> {
         match &self.inner {
             GlyphInner::Char(ch) => screen.print_char(*ch)?,
-            GlyphInner::Str(st) => screen.print_str(&st)?,
+            GlyphInner::Str(st) => screen.print_str(st)?,
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn control() {
+        assert!('\t'.into_glyph().is_err());
+        assert!("a\t".into_glyph().is_err());
+        assert!("a\u{7}".into_glyph().is_err());
+        assert!("a\u{308}".into_glyph().is_ok());
+    }
+}
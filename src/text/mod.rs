@@ -4,14 +4,30 @@
 //
 //! Text styles and themes
 
+mod bidi;
 mod color;
+mod config;
+mod control;
 mod glyph;
 mod outline;
+mod preset;
+mod span;
 mod style;
+mod syntax;
 mod theme;
 
-pub use color::{Color, Intensity};
+pub(crate) use bidi::{reorder_spans, reorder_str};
+pub use bidi::{Align, Direction};
+pub use color::{Color, ColorDepth, Intensity};
+pub use control::ControlChars;
+pub(crate) use control::{sanitize, sanitize_lines};
+pub(crate) use glyph::{fit_str, grapheme_width};
 pub use glyph::{Glyph, IntoGlyph};
 pub use outline::{Corner, Outline, Stroke};
-pub use style::{Appearance, TextStyle, Weight};
+pub use preset::ThemeRegistry;
+pub use span::Span;
+pub(crate) use span::{line_width, push_span, skip_columns, wrap_spans};
+pub(crate) use style::detect_extended_underline;
+pub use style::{Appearance, TextStyle, Underline, Weight};
+pub use syntax::{Syntax, Token, TokenKind, Tokenizer};
 pub use theme::{StyleGroup, Theme, WidgetGroup};
@@ -2,6 +2,9 @@
 //
 // Copyright (c) 2020  Douglas P Lau
 //
+use crate::Error;
+use std::fmt;
+use std::str::FromStr;
 
 /// Outline corner style
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -29,6 +32,9 @@ pub enum Stroke {
 /// - **Block Elements** (U+2580 - U+259F)
 /// - **Geometric Shapes** (U+25A0 - U+25FF)
 /// - **Symbols For Legacy Computing** (U+1FB00 - U+1FBFF)
+///
+/// Outlines can be parsed from strings, such as `"light"`, `"light dashed"`,
+/// `"light rounded"`, `"heavy"`, `"double"` or `"half inner"`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Outline {
     /// Empty outline (all spaces)
@@ -334,4 +340,101 @@ impl Outline {
             _ => right.right(),
         }
     }
+
+    /// Get tee character at left edge (joining an edge to the right)
+    pub fn left_tee(self) -> char {
+        use Outline::*;
+        match self {
+            Light(_, _) => '├',
+            Heavy(_) => '┣',
+            Double => '╠',
+            _ => self.left(),
+        }
+    }
+}
+
+impl FromStr for Outline {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Outline::*;
+        let name = s.to_ascii_lowercase();
+        let words: Vec<&str> = name.split_whitespace().collect();
+        let outline = match words[..] {
+            ["empty"] => Empty,
+            ["light"] => Light(Stroke::Solid, Corner::Square),
+            ["light", "dashed"] => Light(Stroke::Dashed, Corner::Square),
+            ["light", "rounded"] => Light(Stroke::Solid, Corner::Rounded),
+            ["light", "dashed", "rounded"] => {
+                Light(Stroke::Dashed, Corner::Rounded)
+            }
+            ["heavy"] => Heavy(Stroke::Solid),
+            ["heavy", "dashed"] => Heavy(Stroke::Dashed),
+            ["double"] => Double,
+            ["tight"] => Tight,
+            ["half", "inner"] => HalfInner,
+            ["half", "outer"] => HalfOuter,
+            ["block"] => Block,
+            ["medium", "shade"] => MediumShade,
+            _ => return Err(Error::InvalidOutline(s.to_string())),
+        };
+        Ok(outline)
+    }
+}
+
+impl fmt::Display for Outline {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use Outline::*;
+        let name = match self {
+            Empty => "empty",
+            Light(Stroke::Solid, Corner::Square) => "light",
+            Light(Stroke::Dashed, Corner::Square) => "light dashed",
+            Light(Stroke::Solid, Corner::Rounded) => "light rounded",
+            Light(Stroke::Dashed, Corner::Rounded) => "light dashed rounded",
+            Heavy(Stroke::Solid) => "heavy",
+            Heavy(Stroke::Dashed) => "heavy dashed",
+            Double => "double",
+            Tight => "tight",
+            HalfInner => "half inner",
+            HalfOuter => "half outer",
+            Block => "block",
+            MediumShade => "medium shade",
+        };
+        write!(fmt, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let outlines = [
+            Outline::Empty,
+            Outline::Light(Stroke::Solid, Corner::Square),
+            Outline::Light(Stroke::Dashed, Corner::Square),
+            Outline::Light(Stroke::Solid, Corner::Rounded),
+            Outline::Light(Stroke::Dashed, Corner::Rounded),
+            Outline::Heavy(Stroke::Solid),
+            Outline::Heavy(Stroke::Dashed),
+            Outline::Double,
+            Outline::Tight,
+            Outline::HalfInner,
+            Outline::HalfOuter,
+            Outline::Block,
+            Outline::MediumShade,
+        ];
+        for outline in &outlines {
+            assert_eq!(
+                outline.to_string().parse::<Outline>().unwrap(),
+                *outline
+            );
+        }
+        assert_eq!(
+            "Half  Outer".parse::<Outline>().unwrap(),
+            Outline::HalfOuter
+        );
+        assert!("dashed".parse::<Outline>().is_err());
+    }
 }
@@ -0,0 +1,346 @@
+// preset.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::text::{Color, Intensity, Outline, Stroke, Theme};
+use crate::widget::BorderStyle;
+
+/// Light theme: dark text on a white background
+fn light() -> Theme {
+    use Intensity::*;
+    Theme {
+        background: Color::White(Bright),
+        foreground: Color::Black(Normal),
+        primary: Color::Blue(Normal),
+        focused: Color::Cyan(Normal),
+        interacting: Color::Magenta(Normal),
+        dark_shadow: Color::Black(Bright),
+        light_shadow: Color::White(Normal),
+        keyword: Color::Blue(Normal),
+        type_name: Color::Cyan(Normal),
+        name: Color::Green(Normal),
+        string: Color::Red(Normal),
+        number: Color::Magenta(Normal),
+        comment: Color::Black(Bright),
+        error: Color::Red(Normal),
+        warning: Color::Yellow(Normal),
+        success: Color::Green(Normal),
+        info: Color::Blue(Normal),
+        muted: Color::Black(Bright),
+        secondary: Color::Cyan(Normal),
+        ..Theme::default()
+    }
+}
+
+/// Dark theme: light text on a black background
+fn dark() -> Theme {
+    use Intensity::*;
+    Theme {
+        background: Color::Black(Normal),
+        foreground: Color::White(Normal),
+        primary: Color::Cyan(Bright),
+        focused: Color::Blue(Bright),
+        interacting: Color::Yellow(Bright),
+        dark_shadow: Color::Black(Bright),
+        light_shadow: Color::White(Normal),
+        keyword: Color::Magenta(Bright),
+        type_name: Color::Yellow(Bright),
+        name: Color::Cyan(Bright),
+        string: Color::Green(Bright),
+        number: Color::Red(Bright),
+        comment: Color::Black(Bright),
+        error: Color::Red(Bright),
+        warning: Color::Yellow(Bright),
+        success: Color::Green(Bright),
+        info: Color::Cyan(Bright),
+        muted: Color::Black(Bright),
+        secondary: Color::Cyan(Normal),
+        ..Theme::default()
+    }
+}
+
+/// Solarized-like theme, using `Rgb` colors
+fn solarized() -> Theme {
+    Theme {
+        background: Color::Rgb(0x00, 0x2B, 0x36),
+        foreground: Color::Rgb(0x83, 0x94, 0x96),
+        primary: Color::Rgb(0xB5, 0x89, 0x00),
+        focused: Color::Rgb(0x26, 0x8B, 0xD2),
+        interacting: Color::Rgb(0xCB, 0x4B, 0x16),
+        dark_shadow: Color::Rgb(0x07, 0x36, 0x42),
+        light_shadow: Color::Rgb(0x58, 0x6E, 0x75),
+        keyword: Color::Rgb(0x85, 0x99, 0x00),
+        type_name: Color::Rgb(0xB5, 0x89, 0x00),
+        name: Color::Rgb(0x26, 0x8B, 0xD2),
+        string: Color::Rgb(0x2A, 0xA1, 0x98),
+        number: Color::Rgb(0xD3, 0x36, 0x82),
+        comment: Color::Rgb(0x58, 0x6E, 0x75),
+        error: Color::Rgb(0xDC, 0x32, 0x2F),
+        warning: Color::Rgb(0xCB, 0x4B, 0x16),
+        success: Color::Rgb(0x85, 0x99, 0x00),
+        info: Color::Rgb(0x2A, 0xA1, 0x98),
+        muted: Color::Rgb(0x58, 0x6E, 0x75),
+        secondary: Color::Rgb(0x93, 0xA1, 0xA1),
+        ..Theme::default()
+    }
+}
+
+/// Monochrome theme: black, gray and white only
+///
+/// Primary, focused and interacting elements use distinct shades, since they
+/// cannot be told apart by hue.
+fn monochrome() -> Theme {
+    use Intensity::*;
+    Theme {
+        background: Color::Black(Normal),
+        foreground: Color::White(Normal),
+        primary: Color::White(Bright),
+        focused: Color::White(Normal),
+        interacting: Color::Black(Bright),
+        dark_shadow: Color::Black(Bright),
+        light_shadow: Color::White(Normal),
+        keyword: Color::White(Bright),
+        type_name: Color::White(Normal),
+        name: Color::White(Normal),
+        string: Color::White(Normal),
+        number: Color::White(Normal),
+        comment: Color::White(Normal),
+        error: Color::White(Bright),
+        warning: Color::White(Bright),
+        success: Color::White(Normal),
+        info: Color::White(Normal),
+        muted: Color::Black(Bright),
+        secondary: Color::White(Normal),
+        ..Theme::default()
+    }
+}
+
+/// High-contrast theme, for low vision
+///
+/// Only bright colors are used on black, with heavy outlines.
+fn high_contrast() -> Theme {
+    use Intensity::*;
+    let heavy = Outline::Heavy(Stroke::Solid);
+    Theme {
+        background: Color::Black(Normal),
+        foreground: Color::White(Bright),
+        primary: Color::Yellow(Bright),
+        focused: Color::Cyan(Bright),
+        interacting: Color::Yellow(Bright),
+        dark_shadow: Color::White(Bright),
+        light_shadow: Color::White(Bright),
+        keyword: Color::Yellow(Bright),
+        type_name: Color::Cyan(Bright),
+        name: Color::Green(Bright),
+        string: Color::Magenta(Bright),
+        number: Color::Cyan(Bright),
+        comment: Color::Green(Bright),
+        error: Color::Red(Bright),
+        warning: Color::Yellow(Bright),
+        success: Color::Green(Bright),
+        info: Color::Cyan(Bright),
+        muted: Color::White(Bright),
+        secondary: Color::White(Bright),
+        normal_border: BorderStyle::Simple(heavy),
+        button_border: BorderStyle::Simple(heavy),
+        ..Theme::default()
+    }
+}
+
+/// Registry of named themes
+///
+/// A new registry contains the built-in presets:
+/// - `default`: [Theme::default]
+/// - `light`: Dark text on a white background
+/// - `dark`: Light text on a black background
+/// - `solarized`: Solarized-like palette (requires *true color*)
+/// - `monochrome`: Black, gray and white only
+/// - `high-contrast`: Bright colors and heavy outlines, for low vision
+///
+/// ```rust
+/// use semtext::text::{Theme, ThemeRegistry};
+///
+/// let mut registry = ThemeRegistry::new();
+/// registry.register("mine", Theme::default().with_tab_stop(4));
+/// let theme = registry.get("high-contrast").unwrap();
+/// ```
+///
+/// [Theme::default]: struct.Theme.html#impl-Default
+#[derive(Clone, Debug)]
+pub struct ThemeRegistry {
+    /// Named themes, in registration order
+    themes: Vec<(String, Theme)>,
+}
+
+impl Default for ThemeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThemeRegistry {
+    /// Create a new registry containing the built-in presets
+    pub fn new() -> Self {
+        let themes = vec![
+            ("default".to_string(), Theme::default()),
+            ("light".to_string(), light()),
+            ("dark".to_string(), dark()),
+            ("solarized".to_string(), solarized()),
+            ("monochrome".to_string(), monochrome()),
+            ("high-contrast".to_string(), high_contrast()),
+        ];
+        Self { themes }
+    }
+
+    /// Get the theme names, in registration order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.themes.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Get a theme by name (ignoring ASCII case)
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, theme)| theme)
+    }
+
+    /// Register a named theme
+    ///
+    /// If a theme with the same name exists, it is replaced.
+    pub fn register(&mut self, name: &str, theme: Theme) {
+        match self
+            .themes
+            .iter_mut()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        {
+            Some((_, t)) => *t = theme,
+            None => self.themes.push((name.to_string(), theme)),
+        }
+    }
+}
+
+impl Theme {
+    /// Get a built-in theme preset by name
+    ///
+    /// See [ThemeRegistry] for the preset names.
+    ///
+    /// [ThemeRegistry]: struct.ThemeRegistry.html
+    pub fn preset(name: &str) -> Option<Self> {
+        ThemeRegistry::new().get(name).cloned()
+    }
+
+    /// Generate a theme from base colors
+    ///
+    /// * `background`: Background color
+    /// * `accent`: Accent color for primary widgets, or `None` to use the
+    ///   complement of `background`
+    ///
+    /// The foreground is black or white, whichever contrasts more with the
+    /// background.  Other colors are derived to be readable on the
+    /// background, and the shadows used by [BorderStyle::Bevel] are darker
+    /// and lighter shades of it.
+    ///
+    /// [BorderStyle::Bevel]: ../widget/enum.BorderStyle.html#variant.Bevel
+    pub fn from_colors(background: Color, accent: Option<Color>) -> Self {
+        let black = Color::Rgb(0, 0, 0);
+        let white = Color::Rgb(0xFF, 0xFF, 0xFF);
+        let foreground = if background.contrast_ratio(white)
+            >= background.contrast_ratio(black)
+        {
+            white
+        } else {
+            black
+        };
+        let readable = |clr: Color| clr.with_contrast(background, 4.5);
+        let primary =
+            readable(accent.unwrap_or_else(|| background.complement()));
+        let focused = readable(primary.complement());
+        let interacting = readable(primary.blend(foreground, 0.5));
+        let muted = background
+            .blend(foreground, 0.5)
+            .with_contrast(background, 3.0);
+        let status = |clr: Color| readable(clr.blend(primary, 0.2));
+        Theme {
+            background,
+            foreground,
+            primary,
+            focused,
+            interacting,
+            dark_shadow: background.darken(0.4),
+            light_shadow: background.lighten(0.4),
+            keyword: primary,
+            type_name: focused,
+            name: readable(primary.blend(foreground, 0.3)),
+            string: status(Color::Rgb(0x40, 0xA0, 0x40)),
+            number: status(Color::Rgb(0xA0, 0x40, 0xA0)),
+            comment: muted,
+            error: status(Color::Rgb(0xD0, 0x30, 0x30)),
+            warning: status(Color::Rgb(0xD0, 0xA0, 0x20)),
+            success: status(Color::Rgb(0x30, 0xA0, 0x30)),
+            info: status(Color::Rgb(0x30, 0x90, 0xC0)),
+            muted,
+            secondary: readable(focused.blend(foreground, 0.3)),
+            ..Theme::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        let registry = ThemeRegistry::new();
+        let names: Vec<&str> = registry.names().collect();
+        assert_eq!(
+            names,
+            [
+                "default",
+                "light",
+                "dark",
+                "solarized",
+                "monochrome",
+                "high-contrast"
+            ]
+        );
+        for name in &names {
+            let theme = Theme::preset(name).unwrap();
+            assert_ne!(theme.foreground, theme.background);
+        }
+        assert_eq!(Theme::preset("Light"), Some(light()));
+        let mono = monochrome();
+        assert_ne!(mono.primary, mono.focused);
+        assert_ne!(mono.primary, mono.interacting);
+        assert_ne!(mono.focused, mono.interacting);
+        assert_eq!(Theme::preset("none"), None);
+    }
+
+    #[test]
+    fn from_colors() {
+        let bg = Color::Rgb(0x10, 0x20, 0x30);
+        let theme = Theme::from_colors(bg, None);
+        assert_eq!(theme.foreground, Color::Rgb(0xFF, 0xFF, 0xFF));
+        assert!(theme.primary.contrast_ratio(bg) >= 4.5);
+        assert!(theme.dark_shadow.luminance() < bg.luminance());
+        assert!(theme.light_shadow.luminance() > bg.luminance());
+        let bg = Color::Rgb(0xF0, 0xF0, 0xE0);
+        let accent = Color::Rgb(0x00, 0x60, 0xC0);
+        let theme = Theme::from_colors(bg, Some(accent));
+        assert_eq!(theme.foreground, Color::Rgb(0, 0, 0));
+        assert_eq!(theme.primary, accent);
+        assert!(theme.error.contrast_ratio(bg) >= 4.5);
+    }
+
+    #[test]
+    fn register() {
+        let mut registry = ThemeRegistry::new();
+        let theme = Theme::default().with_tab_stop(4);
+        registry.register("custom", theme.clone());
+        registry.register("Dark", theme.clone());
+        assert_eq!(registry.names().count(), 7);
+        assert_eq!(registry.get("custom"), Some(&theme));
+        assert_eq!(registry.get("dark"), Some(&theme));
+    }
+}
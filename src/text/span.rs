@@ -0,0 +1,335 @@
+// span.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::text::{grapheme_width, Appearance, StyleGroup, TextStyle, Theme};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Span of styled text
+///
+/// The style of a span is determined by a [StyleGroup] from the [Theme],
+/// with an optional [Appearance] override.  A span can also be a hyperlink,
+/// which many terminals make clickable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    /// Text of span
+    text: String,
+    /// Style group
+    group: StyleGroup,
+    /// Appearance override
+    appearance: Option<Appearance>,
+    /// Hyperlink target
+    link: Option<String>,
+}
+
+impl Span {
+    /// Create a new span of text
+    pub fn new(text: &str) -> Self {
+        let text = text.to_string();
+        let group = StyleGroup::Enabled;
+        let appearance = None;
+        let link = None;
+        Self {
+            text,
+            group,
+            appearance,
+            link,
+        }
+    }
+
+    /// Set the style group
+    pub fn with_group(mut self, group: StyleGroup) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Set the appearance (overriding [Theme])
+    pub fn with_appearance(mut self, app: Appearance) -> Self {
+        self.appearance = Some(app);
+        self
+    }
+
+    /// Set the hyperlink target (such as a URL)
+    pub fn with_link(mut self, link: &str) -> Self {
+        self.link = Some(link.to_string());
+        self
+    }
+
+    /// Get the span text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the style group
+    pub fn group(&self) -> StyleGroup {
+        self.group
+    }
+
+    /// Get the hyperlink target
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    /// Get the width in text cells
+    pub fn width(&self) -> usize {
+        self.text.width()
+    }
+
+    /// Get the text style
+    pub fn style(&self, theme: &Theme) -> TextStyle {
+        self.apply_appearance(theme.style(self.group))
+    }
+
+    /// Apply the appearance override to a style
+    pub(crate) fn apply_appearance(&self, style: TextStyle) -> TextStyle {
+        match self.appearance {
+            Some(app) => style.with_appearance(app),
+            None => style,
+        }
+    }
+
+    /// Create a span with the same style, but different text
+    pub(crate) fn with_text(&self, text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            group: self.group,
+            appearance: self.appearance,
+            link: self.link.clone(),
+        }
+    }
+
+    /// Check if another span has the same style
+    fn same_style(&self, rhs: &Self) -> bool {
+        self.group == rhs.group
+            && self.appearance == rhs.appearance
+            && self.link == rhs.link
+    }
+}
+
+/// Push text onto a line of spans, merging with the last span if possible
+pub(crate) fn push_span(line: &mut Vec<Span>, span: Span) {
+    if span.text.is_empty() {
+        return;
+    }
+    match line.last_mut() {
+        Some(last) if last.same_style(&span) => last.text.push_str(&span.text),
+        _ => line.push(span),
+    }
+}
+
+/// Get the width of a line of spans
+pub(crate) fn line_width(line: &[Span]) -> usize {
+    line.iter().map(Span::width).sum()
+}
+
+/// Skip columns from the start of a line of spans
+///
+/// A wide grapheme cluster which straddles the edge is replaced with a space.
+pub(crate) fn skip_columns(line: &[Span], cols: usize) -> Vec<Span> {
+    let mut out = vec![];
+    let mut col = 0;
+    for span in line {
+        if col >= cols {
+            push_span(&mut out, span.clone());
+            continue;
+        }
+        let mut text = String::new();
+        for grapheme in span.text.graphemes(true) {
+            if col >= cols {
+                text.push_str(grapheme);
+            } else {
+                col += grapheme_width(grapheme);
+                if col > cols {
+                    text.push(' ');
+                }
+            }
+        }
+        push_span(&mut out, span.with_text(&text));
+    }
+    out
+}
+
+/// Split text after each space, keeping spaces at the end of pieces
+fn split_after_spaces(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest.find(' ').map_or(rest.len(), |i| i + 1);
+        let (piece, tail) = rest.split_at(end);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+/// Span line wrapper
+struct Wrapper {
+    /// Wrap width in text cells
+    width: usize,
+    /// Wrapped lines
+    lines: Vec<Vec<Span>>,
+    /// Current line
+    line: Vec<Span>,
+    /// Width of current line
+    col: usize,
+    /// Pending word, which can cross span boundaries
+    word: Vec<Span>,
+}
+
+impl Wrapper {
+    /// Create a new span line wrapper
+    fn new(width: usize) -> Self {
+        Self {
+            width: width.max(1),
+            lines: vec![],
+            line: vec![],
+            col: 0,
+            word: vec![],
+        }
+    }
+
+    /// Break the current line
+    fn break_line(&mut self) {
+        self.lines.push(std::mem::take(&mut self.line));
+        self.col = 0;
+    }
+
+    /// Add the pending word to the current line
+    fn push_word(&mut self) {
+        let word = std::mem::take(&mut self.word);
+        // Spaces can only be at the end of a word
+        let spaces = word.last().map_or(0, |span| {
+            span.text.len() - span.text.trim_end_matches(' ').len()
+        });
+        let word_width = line_width(&word) - spaces;
+        if self.col > 0 && self.col + word_width > self.width {
+            self.break_line();
+        }
+        if word_width > self.width {
+            for span in &word {
+                for grapheme in span.text.graphemes(true) {
+                    let w = grapheme_width(grapheme);
+                    if self.col > 0 && self.col + w > self.width {
+                        self.break_line();
+                    }
+                    push_span(&mut self.line, span.with_text(grapheme));
+                    self.col += w;
+                }
+            }
+        } else {
+            for span in word {
+                self.col += span.width();
+                push_span(&mut self.line, span);
+            }
+        }
+    }
+
+    /// Finish wrapping, returning the lines
+    fn finish(mut self) -> Vec<Vec<Span>> {
+        self.push_word();
+        if !self.line.is_empty() || self.lines.is_empty() {
+            self.lines.push(self.line);
+        }
+        self.lines
+    }
+}
+
+/// Wrap spans of text into lines
+///
+/// Lines are broken at spaces when possible, and words which are wider than
+/// the wrap width are broken between grapheme clusters.  A word can contain
+/// multiple spans, such as `**bold**text`.  A `\n` in any span forces a line
+/// break.
+///
+/// * `spans`: Spans of text to wrap
+/// * `width`: Wrap width in text cells
+pub(crate) fn wrap_spans(spans: &[Span], width: usize) -> Vec<Vec<Span>> {
+    let mut wrapper = Wrapper::new(width);
+    for span in spans {
+        for (i, text) in span.text.split('\n').enumerate() {
+            if i > 0 {
+                wrapper.push_word();
+                wrapper.break_line();
+            }
+            for piece in split_after_spaces(text) {
+                push_span(&mut wrapper.word, span.with_text(piece));
+                if piece.ends_with(' ') {
+                    wrapper.push_word();
+                }
+            }
+        }
+    }
+    wrapper.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::Weight;
+
+    fn texts(lines: &[Vec<Span>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.iter().map(Span::text).collect())
+            .collect()
+    }
+
+    #[test]
+    fn wrap_words() {
+        let spans = [Span::new("This is some "), Span::new("wrapped text")];
+        let lines = wrap_spans(&spans, 10);
+        assert_eq!(texts(&lines), ["This is ", "some ", "wrapped ", "text"]);
+        assert_eq!(lines[0].len(), 1);
+    }
+
+    #[test]
+    fn wrap_styles() {
+        let bold = Appearance::default().with_underline(true);
+        let spans = [
+            Span::new("one "),
+            Span::new("two").with_appearance(bold),
+            Span::new(" three"),
+        ];
+        let lines = wrap_spans(&spans, 8);
+        assert_eq!(texts(&lines), ["one two ", "three"]);
+        assert_eq!(lines[0].len(), 3);
+    }
+
+    #[test]
+    fn wrap_across_spans() {
+        let bold = Appearance::default().with_weight(Weight::Bold);
+        let spans = [
+            Span::new("hello foo").with_appearance(bold),
+            Span::new("bar baz"),
+        ];
+        let lines = wrap_spans(&spans, 9);
+        assert_eq!(texts(&lines), ["hello ", "foobar ", "baz"]);
+        assert_eq!(lines[1].len(), 2);
+    }
+
+    #[test]
+    fn wrap_long() {
+        let spans = [Span::new("abcdefghij 🦀🦀🦀")];
+        let lines = wrap_spans(&spans, 4);
+        assert_eq!(texts(&lines), ["abcd", "efgh", "ij ", "🦀🦀", "🦀"]);
+    }
+
+    #[test]
+    fn skip() {
+        let spans = [Span::new("ab"), Span::new("🦀c")];
+        assert_eq!(texts(&[skip_columns(&spans, 0)]), ["ab🦀c"]);
+        assert_eq!(texts(&[skip_columns(&spans, 1)]), ["b🦀c"]);
+        assert_eq!(texts(&[skip_columns(&spans, 3)]), [" c"]);
+        assert_eq!(texts(&[skip_columns(&spans, 4)]), ["c"]);
+        assert_eq!(texts(&[skip_columns(&spans, 9)]), [""]);
+    }
+
+    #[test]
+    fn wrap_breaks() {
+        let spans = [Span::new("a\nb"), Span::new("\n")];
+        assert_eq!(texts(&wrap_spans(&spans, 4)), ["a", "b"]);
+        assert_eq!(texts(&wrap_spans(&[], 4)), [""]);
+    }
+}
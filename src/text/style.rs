@@ -4,6 +4,7 @@
 //
 use crate::text::{Color, Intensity};
 use crossterm::style::{Attribute, Attributes};
+use std::env;
 
 /// Font weight
 ///
@@ -19,6 +20,26 @@ pub enum Weight {
     Thin,
 }
 
+/// Underline style
+///
+/// NOTE: Styles other than `Single` require terminal support for extended
+///       underlines, and fall back to `Single` otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Underline {
+    /// No underline
+    None,
+    /// Single underline
+    Single,
+    /// Double underline
+    Double,
+    /// Curly (wavy) underline
+    Curly,
+    /// Dotted underline
+    Dotted,
+    /// Dashed underline
+    Dashed,
+}
+
 /// Text appearance
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Appearance {
@@ -26,6 +47,10 @@ pub struct Appearance {
     attributes: Attributes,
     /// Font weight
     weight: Weight,
+    /// Underline style
+    underline: Underline,
+    /// Underline color
+    underline_color: Option<Color>,
 }
 
 /// Text style
@@ -39,23 +64,81 @@ pub struct TextStyle {
     appearance: Appearance,
 }
 
+/// Toggled attributes, with SGR parameters to set and reset
+const TOGGLES: [(Attribute, &str, &str); 6] = [
+    (Attribute::Italic, "3", "23"),
+    (Attribute::SlowBlink, "5", "25"),
+    (Attribute::Reverse, "7", "27"),
+    (Attribute::Hidden, "8", "28"),
+    (Attribute::CrossedOut, "9", "29"),
+    (Attribute::OverLined, "53", "55"),
+];
+
+/// Terminals known to support extended underlines
+const EXTENDED_TERMS: [&str; 5] =
+    ["kitty", "wezterm", "foot", "alacritty", "contour"];
+
+/// Check if the terminal supports extended underlines
+///
+/// These are underline styles (double, curly, etc.) and underline color.
+pub(crate) fn detect_extended_underline() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let vte = env::var("VTE_VERSION").ok().and_then(|v| v.parse().ok());
+    supports_extended_underline(&term, &term_program, vte)
+}
+
+/// Check if a terminal supports extended underlines
+fn supports_extended_underline(
+    term: &str,
+    term_program: &str,
+    vte_version: Option<u32>,
+) -> bool {
+    EXTENDED_TERMS.iter().any(|t| term.contains(t))
+        || term_program == "WezTerm"
+        || term_program == "iTerm.app"
+        || vte_version.map_or(false, |v| v >= 5102)
+}
+
 impl Default for Weight {
     fn default() -> Self {
         Weight::Normal
     }
 }
 
+impl Default for Underline {
+    fn default() -> Self {
+        Underline::None
+    }
+}
+
 impl Weight {
-    /// Get weight attribute
-    fn attribute(self) -> Option<Attribute> {
+    /// Get the SGR parameter
+    fn sgr(self) -> Option<&'static str> {
         match self {
-            Weight::Bold => Some(Attribute::Bold),
-            Weight::Thin => Some(Attribute::Dim),
+            Weight::Bold => Some("1"),
+            Weight::Thin => Some("2"),
             _ => None,
         }
     }
 }
 
+impl Underline {
+    /// Get the SGR parameter
+    ///
+    /// * `extended`: Terminal supports extended underlines
+    fn sgr(self, extended: bool) -> &'static str {
+        match (self, extended) {
+            (Underline::None, _) => "24",
+            (Underline::Single, _) | (_, false) => "4",
+            (Underline::Double, true) => "4:2",
+            (Underline::Curly, true) => "4:3",
+            (Underline::Dotted, true) => "4:4",
+            (Underline::Dashed, true) => "4:5",
+        }
+    }
+}
+
 impl Appearance {
     /// Set font weight
     pub fn with_weight(mut self, weight: Weight) -> Self {
@@ -63,68 +146,115 @@ impl Appearance {
         self
     }
 
-    /// Set `italic` text appearance
-    pub fn with_italic(mut self, enable: bool) -> Self {
+    /// Set or unset an attribute
+    fn with_attribute(mut self, attr: Attribute, enable: bool) -> Self {
         if enable {
-            self.attributes.set(Attribute::Italic);
+            self.attributes.set(attr);
         } else {
-            self.attributes.unset(Attribute::Italic);
+            self.attributes.unset(attr);
         }
         self
     }
 
+    /// Set `italic` text appearance
+    pub fn with_italic(self, enable: bool) -> Self {
+        self.with_attribute(Attribute::Italic, enable)
+    }
+
     /// Set `strikethrough` text appearance
-    pub fn with_strikethrough(mut self, enable: bool) -> Self {
-        if enable {
-            self.attributes.set(Attribute::CrossedOut);
-        } else {
-            self.attributes.unset(Attribute::CrossedOut);
-        }
-        self
+    pub fn with_strikethrough(self, enable: bool) -> Self {
+        self.with_attribute(Attribute::CrossedOut, enable)
     }
 
     /// Set `underline` text appearance
-    pub fn with_underline(mut self, enable: bool) -> Self {
+    ///
+    /// This is the same as `with_underline_style(Underline::Single)`.
+    pub fn with_underline(self, enable: bool) -> Self {
         if enable {
-            self.attributes.set(Attribute::Underlined);
+            self.with_underline_style(Underline::Single)
         } else {
-            self.attributes.unset(Attribute::Underlined);
+            self.with_underline_style(Underline::None)
         }
+    }
+
+    /// Set the underline style
+    pub fn with_underline_style(mut self, underline: Underline) -> Self {
+        self.underline = underline;
         self
     }
 
-    /// Set `reverse` text appearance
-    pub fn with_reverse(mut self, enable: bool) -> Self {
-        if enable {
-            self.attributes.set(Attribute::Reverse);
-        } else {
-            self.attributes.unset(Attribute::Reverse);
-        }
+    /// Set the underline color
+    ///
+    /// - `clr`: Underline color, or `None` for the foreground color
+    ///
+    /// NOTE: This requires terminal support for extended underlines, and is
+    ///       ignored otherwise.
+    pub fn with_underline_color(mut self, clr: Option<Color>) -> Self {
+        self.underline_color = clr;
         self
     }
 
-    /// Get changed attributes
-    pub(crate) fn changed(&self, before: Self) -> Attributes {
-        let mut attr = self.attributes;
+    /// Set `overline` text appearance
+    pub fn with_overline(self, enable: bool) -> Self {
+        self.with_attribute(Attribute::OverLined, enable)
+    }
+
+    /// Set `reverse` text appearance
+    pub fn with_reverse(self, enable: bool) -> Self {
+        self.with_attribute(Attribute::Reverse, enable)
+    }
+
+    /// Set `blink` text appearance
+    pub fn with_blink(self, enable: bool) -> Self {
+        self.with_attribute(Attribute::SlowBlink, enable)
+    }
+
+    /// Set `hidden` text appearance
+    pub fn with_hidden(self, enable: bool) -> Self {
+        self.with_attribute(Attribute::Hidden, enable)
+    }
+
+    /// Get the underline style
+    pub fn underline(&self) -> Underline {
+        self.underline
+    }
+
+    /// Get the underline color
+    pub fn underline_color(&self) -> Option<Color> {
+        self.underline_color
+    }
+
+    /// Get the SGR sequence for changed attributes
+    ///
+    /// Only attributes which differ from `before` are set or reset.  The
+    /// underline color is not included.
+    ///
+    /// * `before`: Appearance before change
+    /// * `extended`: Terminal supports extended underlines
+    pub(crate) fn changed(&self, before: Self, extended: bool) -> String {
+        let mut params = vec![];
         if self.weight != before.weight {
-            match self.weight.attribute() {
-                Some(w) => attr.set(w),
-                None => attr.set(Attribute::NormalIntensity),
+            if before.weight != Weight::Normal {
+                params.push("22");
             }
+            params.extend(self.weight.sgr());
         }
-        if before.attributes.has(Attribute::Italic) {
-            attr.set(Attribute::NoItalic);
-        }
-        if before.attributes.has(Attribute::CrossedOut) {
-            attr.set(Attribute::NotCrossedOut);
+        for (attr, set, reset) in TOGGLES.iter() {
+            match (self.attributes.has(*attr), before.attributes.has(*attr)) {
+                (true, false) => params.push(set),
+                (false, true) => params.push(reset),
+                _ => (),
+            }
         }
-        if before.attributes.has(Attribute::Underlined) {
-            attr.set(Attribute::NoUnderline);
+        let underline = self.underline.sgr(extended);
+        if underline != before.underline.sgr(extended) {
+            params.push(underline);
         }
-        if before.attributes.has(Attribute::Reverse) {
-            attr.set(Attribute::NoReverse);
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("\x1B[{}m", params.join(";"))
         }
-        attr
     }
 }
 
@@ -175,3 +305,55 @@ impl TextStyle {
         self.appearance
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn changed() {
+        let none = Appearance::default();
+        let bold = none.with_weight(Weight::Bold);
+        let thin = none.with_weight(Weight::Thin);
+        assert_eq!(none.changed(none, true), "");
+        assert_eq!(bold.changed(none, true), "\x1B[1m");
+        assert_eq!(thin.changed(bold, true), "\x1B[22;2m");
+        assert_eq!(none.changed(thin, true), "\x1B[22m");
+        let italic = none.with_italic(true);
+        assert_eq!(italic.changed(italic, true), "");
+        let both = italic.with_reverse(true).with_blink(true);
+        assert_eq!(both.changed(italic, true), "\x1B[5;7m");
+        assert_eq!(none.changed(both, true), "\x1B[23;25;27m");
+        let over = none.with_overline(true).with_hidden(true);
+        assert_eq!(over.changed(none, true), "\x1B[8;53m");
+        assert_eq!(none.changed(over, true), "\x1B[28;55m");
+    }
+
+    #[test]
+    fn extended() {
+        assert!(supports_extended_underline("xterm-kitty", "", None));
+        assert!(supports_extended_underline(
+            "xterm-256color",
+            "",
+            Some(6003)
+        ));
+        assert!(!supports_extended_underline(
+            "xterm-256color",
+            "",
+            Some(5000)
+        ));
+        assert!(!supports_extended_underline("linux", "", None));
+    }
+
+    #[test]
+    fn underline() {
+        let none = Appearance::default();
+        let single = none.with_underline(true);
+        let curly = none.with_underline_style(Underline::Curly);
+        assert_eq!(curly.changed(none, true), "\x1B[4:3m");
+        assert_eq!(curly.changed(single, true), "\x1B[4:3m");
+        assert_eq!(curly.changed(none, false), "\x1B[4m");
+        assert_eq!(curly.changed(single, false), "");
+        assert_eq!(none.changed(curly, true), "\x1B[24m");
+    }
+}
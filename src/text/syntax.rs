@@ -0,0 +1,531 @@
+// syntax.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use std::ops::Range;
+
+/// Kind of syntax token
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TokenKind {
+    /// Plain text
+    Plain,
+    /// Keyword
+    Keyword,
+    /// Type name
+    Type,
+    /// Name of a key, variable, attribute or macro
+    Name,
+    /// String literal
+    String,
+    /// Number literal
+    Number,
+    /// Comment
+    Comment,
+    /// Punctuation or operator
+    Punctuation,
+}
+
+/// Syntax token
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    /// Kind of token
+    pub kind: TokenKind,
+    /// Byte range within source code
+    pub range: Range<usize>,
+}
+
+/// Source code tokenizer
+///
+/// Tokenizers split source code into [Token]s for syntax highlighting.  Any
+/// code not covered by a token is treated as [TokenKind::Plain].
+pub trait Tokenizer {
+    /// Split source code into tokens
+    ///
+    /// Tokens must be in order and must not overlap.
+    fn tokenize(&self, code: &str) -> Vec<Token>;
+}
+
+/// Built-in syntax tokenizers
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Syntax {
+    /// Plain text (no highlighting)
+    Plain,
+    /// Rust source code
+    Rust,
+    /// JSON data
+    Json,
+    /// TOML configuration
+    Toml,
+    /// Shell script
+    Shell,
+}
+
+/// Lexical rules for a syntax
+struct Rules {
+    /// Line comment prefixes
+    line_comments: &'static [&'static str],
+    /// Block comment start and end
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Multi-line string delimiters
+    long_strings: &'static [&'static str],
+    /// String quote characters
+    quotes: &'static [char],
+    /// Keywords
+    keywords: &'static [&'static str],
+    /// Type names
+    types: &'static [&'static str],
+    /// Extra characters allowed in words
+    word_chars: &'static [char],
+}
+
+/// Rust rules
+const RUST: Rules = Rules {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    long_strings: &[],
+    quotes: &['"'],
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn",
+        "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in",
+        "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+        "self", "Self", "static", "struct", "super", "trait", "true", "type",
+        "unsafe", "use", "where", "while",
+    ],
+    types: &[
+        "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128",
+        "isize", "str", "u8", "u16", "u32", "u64", "u128", "usize",
+    ],
+    word_chars: &['_'],
+};
+
+/// JSON rules
+const JSON: Rules = Rules {
+    line_comments: &[],
+    block_comment: None,
+    long_strings: &[],
+    quotes: &['"'],
+    keywords: &["false", "null", "true"],
+    types: &[],
+    word_chars: &[],
+};
+
+/// TOML rules
+const TOML: Rules = Rules {
+    line_comments: &["#"],
+    block_comment: None,
+    long_strings: &["\"\"\"", "'''"],
+    quotes: &['"', '\''],
+    keywords: &["false", "inf", "nan", "true"],
+    types: &[],
+    word_chars: &['_', '-'],
+};
+
+/// Shell rules
+const SHELL: Rules = Rules {
+    line_comments: &["#"],
+    block_comment: None,
+    long_strings: &[],
+    quotes: &['"', '\''],
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for",
+        "function", "if", "in", "local", "return", "select", "then", "until",
+        "while",
+    ],
+    types: &[],
+    word_chars: &['_', '-'],
+};
+
+impl Syntax {
+    /// Get the lexical rules
+    fn rules(self) -> Option<&'static Rules> {
+        match self {
+            Syntax::Plain => None,
+            Syntax::Rust => Some(&RUST),
+            Syntax::Json => Some(&JSON),
+            Syntax::Toml => Some(&TOML),
+            Syntax::Shell => Some(&SHELL),
+        }
+    }
+}
+
+impl Tokenizer for Syntax {
+    fn tokenize(&self, code: &str) -> Vec<Token> {
+        match self.rules() {
+            Some(rules) => Lexer::new(*self, rules, code).tokens(),
+            None => vec![],
+        }
+    }
+}
+
+/// Simple lexer for built-in syntaxes
+struct Lexer<'a> {
+    /// Syntax being lexed
+    syntax: Syntax,
+    /// Lexical rules
+    rules: &'static Rules,
+    /// Source code
+    code: &'a str,
+    /// Current byte position
+    pos: usize,
+    /// Tokens found
+    tokens: Vec<Token>,
+}
+
+impl<'a> Lexer<'a> {
+    /// Create a new lexer
+    fn new(syntax: Syntax, rules: &'static Rules, code: &'a str) -> Self {
+        Self {
+            syntax,
+            rules,
+            code,
+            pos: 0,
+            tokens: vec![],
+        }
+    }
+
+    /// Get remaining source code
+    fn rest(&self) -> &'a str {
+        &self.code[self.pos..]
+    }
+
+    /// Push a token ending at a byte position
+    fn push(&mut self, kind: TokenKind, end: usize) {
+        let start = self.pos;
+        self.pos = end;
+        if start < end {
+            if let Some(last) = self.tokens.last_mut() {
+                if last.kind == kind && last.range.end == start {
+                    last.range.end = end;
+                    return;
+                }
+            }
+            self.tokens.push(Token {
+                kind,
+                range: start..end,
+            });
+        }
+    }
+
+    /// Get the end position of the current line
+    fn line_end(&self) -> usize {
+        self.rest()
+            .find('\n')
+            .map_or(self.code.len(), |i| self.pos + i)
+    }
+
+    /// Check if the current position is the first non-blank in its line
+    fn at_line_start(&self) -> bool {
+        self.code[..self.pos]
+            .rsplit('\n')
+            .next()
+            .map_or(true, |line| line.trim().is_empty())
+    }
+
+    /// Check if a char can continue a word
+    fn is_word_char(&self, ch: char) -> bool {
+        ch.is_alphanumeric() || self.rules.word_chars.contains(&ch)
+    }
+
+    /// Get the end of a word starting at the current position
+    fn word_end(&self) -> usize {
+        self.rest()
+            .char_indices()
+            .find(|(_, ch)| !self.is_word_char(*ch))
+            .map_or(self.code.len(), |(i, _)| self.pos + i)
+    }
+
+    /// Get the end of a quoted string starting at the current position
+    fn string_end(&self, quote: char) -> usize {
+        let escapes = quote == '"';
+        let mut chars = self.rest().char_indices().skip(1);
+        while let Some((i, ch)) = chars.next() {
+            if ch == quote {
+                return self.pos + i + ch.len_utf8();
+            } else if ch == '\\' && escapes {
+                chars.next();
+            } else if ch == '\n' && self.syntax != Syntax::Shell {
+                return self.pos + i;
+            }
+        }
+        self.code.len()
+    }
+
+    /// Get the end of a bracketed item within the current line
+    fn bracket_end(&self, close: &str) -> usize {
+        let line_end = self.line_end();
+        self.code[self.pos..line_end]
+            .find(close)
+            .map_or(line_end, |i| self.pos + i + close.len())
+    }
+
+    /// Get the next non-blank char after a byte position
+    fn next_non_blank(&self, pos: usize) -> Option<char> {
+        self.code[pos..]
+            .chars()
+            .find(|ch| *ch != ' ' && *ch != '\t')
+    }
+
+    /// Lex all tokens
+    fn tokens(mut self) -> Vec<Token> {
+        while let Some(ch) = self.rest().chars().next() {
+            self.lex_token(ch);
+        }
+        self.tokens
+    }
+
+    /// Lex a token starting with a char
+    fn lex_token(&mut self, ch: char) {
+        let rest = self.rest();
+        let rules = self.rules;
+        if ch.is_whitespace() {
+            self.push(TokenKind::Plain, self.pos + ch.len_utf8());
+        } else if rules.line_comments.iter().any(|c| rest.starts_with(c)) {
+            self.push(TokenKind::Comment, self.line_end());
+        } else if let Some((start, end)) = rules
+            .block_comment
+            .filter(|(start, _)| rest.starts_with(start))
+        {
+            let end =
+                rest[start.len()..].find(end).map_or(self.code.len(), |i| {
+                    self.pos + start.len() + i + end.len()
+                });
+            self.push(TokenKind::Comment, end);
+        } else if let Some(delim) =
+            rules.long_strings.iter().find(|d| rest.starts_with(*d))
+        {
+            let end = rest[delim.len()..]
+                .find(delim)
+                .map_or(self.code.len(), |i| self.pos + 2 * delim.len() + i);
+            self.push(TokenKind::String, end);
+        } else if rules.quotes.contains(&ch) {
+            let end = self.string_end(ch);
+            let kind = match (self.syntax, self.next_non_blank(end)) {
+                (Syntax::Json, Some(':')) => TokenKind::Name,
+                (Syntax::Toml, Some('=')) => TokenKind::Name,
+                _ => TokenKind::String,
+            };
+            self.push(kind, end);
+        } else if ch.is_ascii_digit() {
+            let end = rest
+                .char_indices()
+                .find(|(_, c)| !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+                .map_or(self.code.len(), |(i, _)| self.pos + i);
+            self.push(TokenKind::Number, end);
+        } else if self.is_word_char(ch) {
+            self.lex_word();
+        } else {
+            self.lex_symbol(ch);
+        }
+    }
+
+    /// Lex a word
+    fn lex_word(&mut self) {
+        let end = self.word_end();
+        let word = &self.code[self.pos..end];
+        let next = self.code[end..].chars().next();
+        let kind = if self.rules.keywords.contains(&word) {
+            TokenKind::Keyword
+        } else if self.rules.types.contains(&word) {
+            TokenKind::Type
+        } else {
+            match (self.syntax, next) {
+                (Syntax::Rust, Some('!')) => TokenKind::Name,
+                (Syntax::Rust, _)
+                    if word.starts_with(|c: char| c.is_uppercase()) =>
+                {
+                    TokenKind::Type
+                }
+                (Syntax::Toml, _)
+                    if self.at_line_start()
+                        && self.next_non_blank(end) == Some('=') =>
+                {
+                    TokenKind::Name
+                }
+                _ => TokenKind::Plain,
+            }
+        };
+        self.push(kind, end);
+    }
+
+    /// Lex a symbol
+    fn lex_symbol(&mut self, ch: char) {
+        let len = ch.len_utf8();
+        match (self.syntax, ch) {
+            (Syntax::Rust, '#') => {
+                // attribute
+                self.push(TokenKind::Name, self.bracket_end("]"));
+            }
+            (Syntax::Rust, '\'') => {
+                // char literal or lifetime
+                let rest = &self.rest()[len..];
+                let mut chars = rest.chars();
+                let c1 = chars.next();
+                let end = match (c1, chars.next()) {
+                    (Some('\\'), Some(c2)) => {
+                        // skip escaped char, which may be a quote
+                        let skip = 1 + c2.len_utf8();
+                        rest[skip..]
+                            .find('\'')
+                            .map(|i| self.pos + len + skip + i + 1)
+                    }
+                    (Some(c), Some('\'')) => {
+                        Some(self.pos + len + c.len_utf8() + 1)
+                    }
+                    _ => None,
+                };
+                match end {
+                    Some(end) => self.push(TokenKind::String, end),
+                    None => {
+                        self.pos += len;
+                        let end = self.word_end();
+                        self.pos -= len;
+                        self.push(TokenKind::Type, end);
+                    }
+                }
+            }
+            (Syntax::Toml, '[') if self.at_line_start() => {
+                // table header
+                let close = if self.rest().starts_with("[[") {
+                    "]]"
+                } else {
+                    "]"
+                };
+                self.push(TokenKind::Name, self.bracket_end(close));
+            }
+            (Syntax::Shell, '$') => {
+                let rest = &self.rest()[len..];
+                let end = if rest.starts_with('{') {
+                    rest.find('}')
+                        .map_or(self.line_end(), |i| self.pos + len + i + 1)
+                } else {
+                    self.pos += len;
+                    let mut end = self.word_end();
+                    if end == self.pos {
+                        // special parameter, such as `$?`
+                        end += rest.chars().next().map_or(0, char::len_utf8);
+                    }
+                    self.pos -= len;
+                    end
+                };
+                self.push(TokenKind::Name, end);
+            }
+            _ => self.push(TokenKind::Punctuation, self.pos + len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds(syntax: Syntax, code: &str) -> Vec<(TokenKind, &str)> {
+        syntax
+            .tokenize(code)
+            .into_iter()
+            .filter(|t| t.kind != TokenKind::Plain)
+            .map(|t| (t.kind, &code[t.range]))
+            .collect()
+    }
+
+    #[test]
+    fn rust() {
+        use TokenKind::*;
+        let code = "#[test]\nfn x<'a>(s: &'a str) -> Vec<u8> { // hi\n  \
+                    println!(\"{}\", '\\n', '\\'', 0x1f) }";
+        assert_eq!(
+            kinds(Syntax::Rust, code),
+            [
+                (Name, "#[test]"),
+                (Keyword, "fn"),
+                (Punctuation, "<"),
+                (Type, "'a"),
+                (Punctuation, ">("),
+                (Punctuation, ":"),
+                (Punctuation, "&"),
+                (Type, "'a"),
+                (Type, "str"),
+                (Punctuation, ")"),
+                (Punctuation, "->"),
+                (Type, "Vec"),
+                (Punctuation, "<"),
+                (Type, "u8"),
+                (Punctuation, ">"),
+                (Punctuation, "{"),
+                (Comment, "// hi"),
+                (Name, "println"),
+                (Punctuation, "!("),
+                (String, "\"{}\""),
+                (Punctuation, ","),
+                (String, "'\\n'"),
+                (Punctuation, ","),
+                (String, "'\\''"),
+                (Punctuation, ","),
+                (Number, "0x1f"),
+                (Punctuation, ")"),
+                (Punctuation, "}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn json() {
+        use TokenKind::*;
+        let code = "{\"a\": [1.5, true, \"b\\\"\"]}";
+        assert_eq!(
+            kinds(Syntax::Json, code),
+            [
+                (Punctuation, "{"),
+                (Name, "\"a\""),
+                (Punctuation, ":"),
+                (Punctuation, "["),
+                (Number, "1.5"),
+                (Punctuation, ","),
+                (Keyword, "true"),
+                (Punctuation, ","),
+                (String, "\"b\\\"\""),
+                (Punctuation, "]}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml() {
+        use TokenKind::*;
+        let code = "[package] # pkg\nname = \"x\"\nlong = '''\na\n'''";
+        assert_eq!(
+            kinds(Syntax::Toml, code),
+            [
+                (Name, "[package]"),
+                (Comment, "# pkg"),
+                (Name, "name"),
+                (Punctuation, "="),
+                (String, "\"x\""),
+                (Name, "long"),
+                (Punctuation, "="),
+                (String, "'''\na\n'''"),
+            ]
+        );
+    }
+
+    #[test]
+    fn shell() {
+        use TokenKind::*;
+        let code = "if [ -n \"$X\" ]; then echo ${HOME} $1 $?; fi # done";
+        assert_eq!(
+            kinds(Syntax::Shell, code),
+            [
+                (Keyword, "if"),
+                (Punctuation, "["),
+                (String, "\"$X\""),
+                (Punctuation, "];"),
+                (Keyword, "then"),
+                (Name, "${HOME}"),
+                (Name, "$1"),
+                (Name, "$?"),
+                (Punctuation, ";"),
+                (Keyword, "fi"),
+                (Comment, "# done"),
+            ]
+        );
+    }
+}
@@ -2,7 +2,10 @@
 //
 // Copyright (c) 2020  Douglas P Lau
 //
-use crate::text::{Appearance, Color, Intensity, Outline, TextStyle};
+use crate::text::{
+    Appearance, Color, ControlChars, Intensity, Outline, TextStyle, TokenKind,
+    Weight,
+};
 use crate::widget::BorderStyle;
 
 /// Widget group
@@ -33,6 +36,39 @@ pub enum StyleGroup {
     LightShadow,
     /// Dark shadow style
     DarkShadow,
+    /// Syntax token style
+    Token(TokenKind),
+    /// Error status style
+    Error,
+    /// Warning status style
+    Warning,
+    /// Success status style
+    Success,
+    /// Informational status style
+    Info,
+    /// Muted (de-emphasized) text style
+    Muted,
+    /// Secondary text style
+    Secondary,
+}
+
+impl StyleGroup {
+    /// Adjust an appearance for monochrome terminals
+    ///
+    /// Without colors, attributes are used to distinguish style groups.
+    pub(crate) fn monochrome(self, app: Appearance) -> Appearance {
+        match self {
+            StyleGroup::Primary => app.with_weight(Weight::Bold),
+            StyleGroup::Hovered => app.with_underline(true),
+            StyleGroup::Focused => app.with_reverse(true),
+            StyleGroup::Interacted => {
+                app.with_reverse(true).with_weight(Weight::Bold)
+            }
+            StyleGroup::Disabled => app.with_weight(Weight::Thin),
+            StyleGroup::Warning => app.with_underline(true),
+            _ => app,
+        }
+    }
 }
 
 /// Style theme
@@ -52,10 +88,38 @@ pub struct Theme {
     pub dark_shadow: Color,
     /// Light shadow color
     pub light_shadow: Color,
+    /// Syntax keyword color
+    pub keyword: Color,
+    /// Syntax type name color
+    pub type_name: Color,
+    /// Syntax name color (keys, variables, attributes)
+    pub name: Color,
+    /// Syntax string literal color
+    pub string: Color,
+    /// Syntax number literal color
+    pub number: Color,
+    /// Syntax comment color
+    pub comment: Color,
+    /// Error status color
+    pub error: Color,
+    /// Warning status color
+    pub warning: Color,
+    /// Success status color
+    pub success: Color,
+    /// Informational status color
+    pub info: Color,
+    /// Muted (de-emphasized) text color
+    pub muted: Color,
+    /// Secondary text color
+    pub secondary: Color,
     /// Normal border style
     pub normal_border: BorderStyle,
     /// Button border style
     pub button_border: BorderStyle,
+    /// Number of columns between tab stops
+    pub tab_stop: u16,
+    /// Control character replacement
+    pub control_chars: ControlChars,
 }
 
 impl Default for Theme {
@@ -67,8 +131,22 @@ impl Default for Theme {
         let interacting = Color::Yellow(Intensity::Bright);
         let dark_shadow = Color::Black(Intensity::Bright);
         let light_shadow = Color::White(Intensity::Normal);
+        let keyword = Color::Yellow(Intensity::Bright);
+        let type_name = Color::Cyan(Intensity::Bright);
+        let name = Color::Green(Intensity::Bright);
+        let string = Color::Magenta(Intensity::Bright);
+        let number = Color::Red(Intensity::Bright);
+        let comment = Color::White(Intensity::Normal);
+        let error = Color::Red(Intensity::Bright);
+        let warning = Color::Yellow(Intensity::Bright);
+        let success = Color::Green(Intensity::Bright);
+        let info = Color::Cyan(Intensity::Bright);
+        let muted = Color::White(Intensity::Normal);
+        let secondary = Color::Cyan(Intensity::Normal);
         let normal_border = BorderStyle::Simple(Outline::default());
         let button_border = BorderStyle::Bevel(Outline::default());
+        let tab_stop = 8;
+        let control_chars = ControlChars::default();
         Self {
             background,
             foreground,
@@ -77,8 +155,22 @@ impl Default for Theme {
             interacting,
             dark_shadow,
             light_shadow,
+            keyword,
+            type_name,
+            name,
+            string,
+            number,
+            comment,
+            error,
+            warning,
+            success,
+            info,
+            muted,
+            secondary,
             normal_border,
             button_border,
+            tab_stop,
+            control_chars,
         }
     }
 }
@@ -114,6 +206,18 @@ impl Theme {
         self
     }
 
+    /// Set the number of columns between tab stops
+    pub fn with_tab_stop(mut self, tab_stop: u16) -> Self {
+        self.tab_stop = tab_stop;
+        self
+    }
+
+    /// Set the control character replacement
+    pub fn with_control_chars(mut self, ctrl: ControlChars) -> Self {
+        self.control_chars = ctrl;
+        self
+    }
+
     /// Get text style
     pub fn style(&self, group: StyleGroup) -> TextStyle {
         let style = TextStyle::default().with_background(self.background);
@@ -129,6 +233,40 @@ impl Theme {
                 .with_appearance(Appearance::default().with_reverse(true)),
             StyleGroup::LightShadow => style.with_foreground(self.light_shadow),
             StyleGroup::DarkShadow => style.with_foreground(self.dark_shadow),
+            StyleGroup::Token(kind) => self.token_style(style, kind),
+            StyleGroup::Error => {
+                style.with_foreground(self.error).with_appearance(
+                    Appearance::default().with_weight(Weight::Bold),
+                )
+            }
+            StyleGroup::Warning => style.with_foreground(self.warning),
+            StyleGroup::Success => style.with_foreground(self.success),
+            StyleGroup::Info => style.with_foreground(self.info),
+            StyleGroup::Muted => {
+                style.with_foreground(self.muted).with_appearance(
+                    Appearance::default().with_weight(Weight::Thin),
+                )
+            }
+            StyleGroup::Secondary => style.with_foreground(self.secondary),
+            _ => style.with_foreground(self.foreground),
+        }
+    }
+
+    /// Get syntax token style
+    fn token_style(&self, style: TextStyle, kind: TokenKind) -> TextStyle {
+        match kind {
+            TokenKind::Keyword => {
+                style.with_foreground(self.keyword).with_appearance(
+                    Appearance::default().with_weight(Weight::Bold),
+                )
+            }
+            TokenKind::Type => style.with_foreground(self.type_name),
+            TokenKind::Name => style.with_foreground(self.name),
+            TokenKind::String => style.with_foreground(self.string),
+            TokenKind::Number => style.with_foreground(self.number),
+            TokenKind::Comment => style
+                .with_foreground(self.comment)
+                .with_appearance(Appearance::default().with_italic(true)),
             _ => style.with_foreground(self.foreground),
         }
     }
@@ -141,3 +279,51 @@ impl Theme {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn status_styles() {
+        let theme = Theme::default();
+        let bg = theme.background;
+        let groups = [
+            (StyleGroup::Error, theme.error),
+            (StyleGroup::Warning, theme.warning),
+            (StyleGroup::Success, theme.success),
+            (StyleGroup::Info, theme.info),
+            (StyleGroup::Muted, theme.muted),
+            (StyleGroup::Secondary, theme.secondary),
+        ];
+        for (group, clr) in &groups {
+            let style = theme.style(*group);
+            assert_eq!(style.foreground(), *clr);
+            assert_eq!(style.background(), bg);
+        }
+    }
+
+    #[test]
+    fn monochrome() {
+        let theme = Theme::default();
+        let groups = [
+            StyleGroup::Enabled,
+            StyleGroup::Disabled,
+            StyleGroup::Primary,
+            StyleGroup::Hovered,
+            StyleGroup::Focused,
+            StyleGroup::Interacted,
+        ];
+        let apps: Vec<Appearance> = groups
+            .iter()
+            .map(|g| g.monochrome(theme.style(*g).appearance()))
+            .collect();
+        for (i, app) in apps.iter().enumerate() {
+            for other in &apps[i + 1..] {
+                assert_ne!(app, other);
+            }
+        }
+        let app = StyleGroup::Primary.monochrome(Appearance::default());
+        assert_eq!(app, Appearance::default().with_weight(Weight::Bold));
+    }
+}
@@ -2,10 +2,10 @@
 //
 // Copyright (c) 2020-2021  Douglas P Lau
 //
-use crate::input::{Action, FocusEvent, ModKeys, MouseEvent};
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent};
 use crate::layout::{Cells, Dim, LengthBound, Pos};
-use crate::text::{StyleGroup, Theme, WidgetGroup};
-use crate::widget::{Border, Button, ScrollView};
+use crate::text::{StyleGroup, TextStyle, Theme, WidgetGroup};
+use crate::widget::{Border, Button, ScrollView, Styled};
 use crate::Result;
 
 /// User interface component
@@ -50,7 +50,8 @@ pub trait Widget {
     ///
     /// ## Return
     ///
-    /// If the event triggers a `Redraw` [Action], it is returned.
+    /// If the event triggers a `Redraw` [Action], it is returned.  Widgets
+    /// which return no action when offered focus are skipped by `Tab`.
     fn focus(&self, _fev: FocusEvent) -> Option<Action> {
         None
     }
@@ -76,6 +77,34 @@ pub trait Widget {
         None
     }
 
+    /// Handle a key event
+    ///
+    /// * `_key`: The key pressed
+    /// * `_mods`: Pressed modifier keys
+    ///
+    /// Key events are sent to the focused widget before the [KeyMap].  Focus
+    /// is moved by clicking a widget, or with `Tab` / `BackTab`.
+    ///
+    /// ## Return
+    ///
+    /// If the event triggers an [Action], it is returned.
+    ///
+    /// [KeyMap]: input/struct.KeyMap.html
+    fn key_event(&self, _key: KeyPress, _mods: ModKeys) -> Option<Action> {
+        // ignore by default
+        None
+    }
+
+    /// Get the text cursor position
+    ///
+    /// This is relative to the top-left of the widget, ignoring any
+    /// scrolling offset.  A [ScrollView] uses it to keep the cursor visible.
+    ///
+    /// [ScrollView]: widget/struct.ScrollView.html
+    fn cursor(&self) -> Option<Pos> {
+        None
+    }
+
     /// Wrap the widget with a border
     fn into_border(self) -> Border<Self>
     where
@@ -92,6 +121,26 @@ pub trait Widget {
         Border::new(Button::new(self))
     }
 
+    /// Override a style group for the widget
+    ///
+    /// The override cascades to any wrapped widgets, such as within a
+    /// [Border] or [ScrollView].  Styles which are not overridden fall back
+    /// to the [Theme].
+    ///
+    /// [Border]: widget/struct.Border.html
+    /// [ScrollView]: widget/struct.ScrollView.html
+    /// [Theme]: text/struct.Theme.html
+    fn with_style_override(
+        self,
+        group: StyleGroup,
+        style: TextStyle,
+    ) -> Styled<Self>
+    where
+        Self: Sized,
+    {
+        Styled::new(self).with_style_override(group, style)
+    }
+
     /// Wrap the widget with a scroll view
     fn into_scroll_view(self) -> ScrollView<Self>
     where
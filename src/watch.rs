@@ -0,0 +1,140 @@
+// watch.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Waker};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Interval between file modification checks
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shared watcher state
+#[derive(Default)]
+struct WatchState {
+    /// File changed since last check
+    changed: bool,
+    /// Waker for task awaiting changes
+    waker: Option<Waker>,
+    /// Watcher dropped
+    stop: bool,
+}
+
+/// File watcher
+///
+/// A thread polls the file modification time, waking the awaiting task when
+/// it changes.
+pub(crate) struct FileWatcher {
+    /// Path to watched file
+    path: PathBuf,
+    /// Shared state
+    state: Arc<Mutex<WatchState>>,
+}
+
+/// Get the modification time of a file
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl FileWatcher {
+    /// Start watching a file
+    pub(crate) fn new(path: &Path) -> Self {
+        let path = path.to_path_buf();
+        let state = Arc::new(Mutex::new(WatchState::default()));
+        let watch_path = path.clone();
+        let watch_state = Arc::clone(&state);
+        let mut last = modified(&path);
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let mtime = modified(&watch_path);
+            let mut state = match watch_state.lock() {
+                Ok(state) => state,
+                Err(_) => break,
+            };
+            if state.stop {
+                break;
+            }
+            if mtime != last {
+                last = mtime;
+                state.changed = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+        Self { path, state }
+    }
+
+    /// Get the watched path
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check if the file has changed
+    ///
+    /// If not, the task is woken on the next change.
+    pub(crate) fn poll_changed(&self, cx: &mut Context<'_>) -> bool {
+        match self.state.lock() {
+            Ok(mut state) => {
+                if state.changed {
+                    state.changed = false;
+                    true
+                } else {
+                    state.waker = Some(cx.waker().clone());
+                    false
+                }
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.stop = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::task::noop_waker;
+    use std::time::Instant;
+
+    /// Get a temporary file path unique to a test
+    fn temp_path(test: &str) -> PathBuf {
+        let name = format!("semtext-{}-{}.toml", std::process::id(), test);
+        std::env::temp_dir().join(name)
+    }
+
+    /// Poll a watcher until it reports a change, or a deadline passes
+    fn wait_changed(watcher: &FileWatcher, cx: &mut Context<'_>) -> bool {
+        let deadline = Instant::now() + POLL_INTERVAL * 20;
+        while Instant::now() < deadline {
+            if watcher.poll_changed(cx) {
+                return true;
+            }
+            thread::sleep(POLL_INTERVAL / 5);
+        }
+        false
+    }
+
+    #[test]
+    fn changed() {
+        let path = temp_path("changed");
+        fs::write(&path, "").unwrap();
+        let watcher = FileWatcher::new(&path);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(!watcher.poll_changed(&mut cx));
+        // Removing the file changes its modification time to `None`
+        fs::remove_file(&path).unwrap();
+        assert!(wait_changed(&watcher, &mut cx));
+        assert!(!watcher.poll_changed(&mut cx));
+    }
+}
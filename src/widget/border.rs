@@ -2,10 +2,11 @@
 //
 // Copyright (c) 2020-2021  Douglas P Lau
 //
-use crate::input::{Action, FocusEvent, ModKeys, MouseEvent};
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent};
 use crate::layout::{BBox, Cells, Dim, LengthBound, Pos};
 use crate::text::{Outline, StyleGroup, Theme};
 use crate::{Result, Widget};
+use std::cell::Cell;
 
 /// Border elevation
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -61,6 +62,10 @@ pub struct Border<W: Widget> {
     wrapped: W,
     /// Border style
     border_style: Option<BorderStyle>,
+    /// Style group for edges
+    edge_group: Option<StyleGroup>,
+    /// Inset of wrapped widget (from last draw)
+    inset: Cell<BBox>,
 }
 
 impl Elevation {
@@ -148,9 +153,13 @@ impl<W: Widget> Border<W> {
     /// Create a new border
     pub fn new(wrapped: W) -> Self {
         let border_style = None;
+        let edge_group = None;
+        let inset = Cell::new(BBox::default());
         Self {
             wrapped,
             border_style,
+            edge_group,
+            inset,
         }
     }
 
@@ -170,6 +179,23 @@ impl<W: Widget> Border<W> {
         self
     }
 
+    /// Set the style group for all edges
+    ///
+    /// - `edge_group`: Group to override elevation shading, such as
+    ///   `StyleGroup::Warning`, or `None`
+    pub fn with_edge_group(mut self, edge_group: Option<StyleGroup>) -> Self {
+        self.edge_group = edge_group;
+        self
+    }
+
+    /// Get the style group for an edge
+    fn edge_group(&self, elevation: Elevation, edge: Edge) -> StyleGroup {
+        match (self.edge_group, elevation) {
+            (_, Elevation::Unknown) | (None, _) => elevation.edge_group(edge),
+            (Some(group), _) => group,
+        }
+    }
+
     /// Get the border style
     fn border_style(&self, theme: &Theme) -> BorderStyle {
         self.border_style
@@ -200,14 +226,14 @@ impl<W: Widget> Widget for Border<W> {
         if width == 0 || height == 0 {
             return Ok(());
         }
-        let theme = cells.theme();
-        let bs = self.border_style(theme);
+        let bs = self.border_style(cells.theme());
         let group = self.wrapped.style_group();
         let elevation = Elevation::from_style_group(bs, group);
-        let style_top = theme.style(elevation.edge_group(Edge::Top));
-        let style_left = theme.style(elevation.edge_group(Edge::Left));
-        let style_right = theme.style(elevation.edge_group(Edge::Right));
-        let style_bottom = theme.style(elevation.edge_group(Edge::Bottom));
+        let style_top = cells.style(self.edge_group(elevation, Edge::Top));
+        let style_left = cells.style(self.edge_group(elevation, Edge::Left));
+        let style_right = cells.style(self.edge_group(elevation, Edge::Right));
+        let style_bottom =
+            cells.style(self.edge_group(elevation, Edge::Bottom));
         let inset = bs.inset(BBox::new(0, 0, width, height));
         let mut row = 0;
         if let Some(top) = bs.outline(Edge::Top) {
@@ -263,8 +289,9 @@ impl<W: Widget> Widget for Border<W> {
             }
         }
         cells.clip(Some(inset));
+        self.inset.set(inset);
         // Set style for wrapped widget draw
-        let style = cells.theme().style(group);
+        let style = cells.style(group);
         cells.set_style(style)?;
         self.wrapped.draw(cells, offset)
     }
@@ -282,6 +309,26 @@ impl<W: Widget> Widget for Border<W> {
         dim: Dim,
         pos: Pos,
     ) -> Option<Action> {
-        self.wrapped.mouse_event(mev, mods, dim, pos)
+        let inset = self.inset.get();
+        if inset.dim().is_empty() {
+            return self.wrapped.mouse_event(mev, mods, dim, pos);
+        }
+        // Clamp position on edges to the wrapped widget
+        let col = pos.col.saturating_sub(inset.left()).min(inset.width() - 1);
+        let row = pos.row.saturating_sub(inset.top()).min(inset.height() - 1);
+        self.wrapped
+            .mouse_event(mev, mods, inset.dim(), Pos::new(col, row))
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        self.wrapped.key_event(key, mods)
+    }
+
+    /// Get the text cursor position
+    fn cursor(&self) -> Option<Pos> {
+        let inset = self.inset.get();
+        let pos = Pos::new(inset.left(), inset.top());
+        self.wrapped.cursor().map(|cursor| cursor + pos)
     }
 }
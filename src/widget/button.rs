@@ -2,7 +2,9 @@
 //
 // Copyright (c) 2020-2021  Douglas P Lau
 //
-use crate::input::{Action, FocusEvent, ModKeys, MouseEvent};
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent,
+};
 use crate::layout::{Cells, Dim, LengthBound, Pos};
 use crate::text::{IntoGlyph, StyleGroup, Theme, WidgetGroup};
 use crate::{Result, Widget};
@@ -23,31 +25,133 @@ enum State {
     Pressed,
 }
 
+/// Button interaction state
+///
+/// Tracks disabled / enabled / hovered / focused / pressed states, for
+/// button-like widgets.
+pub(crate) struct ButtonState {
+    /// Current state
+    state: Cell<State>,
+}
+
+impl ButtonState {
+    /// Create a new (enabled) button state
+    pub(crate) fn new() -> Self {
+        let state = Cell::new(State::Enabled);
+        Self { state }
+    }
+
+    /// Disable the button
+    pub(crate) fn disable(&self) {
+        self.state.set(State::Disabled);
+    }
+
+    /// Enable the button
+    pub(crate) fn enable(&self) {
+        if self.state.get() == State::Disabled {
+            self.state.set(State::Enabled);
+        }
+    }
+
+    /// Check if the button is focused (or pressed)
+    pub(crate) fn is_focused(&self) -> bool {
+        matches!(self.state.get(), State::Focused | State::Pressed)
+    }
+
+    /// Check if the button was clicked, on a mouse button up event
+    ///
+    /// The button must have been pressed, then released inside.
+    pub(crate) fn is_clicked(&self, mev: MouseEvent) -> bool {
+        // Releasing inside changes Pressed to Focused before mouse events
+        matches!(mev, MouseEvent::ButtonUp(MouseButton::Left))
+            && self.state.get() == State::Focused
+    }
+
+    /// Set the state, returning `Redraw` if changed
+    fn set(&self, st: State) -> Option<Action> {
+        if st != self.state.get() {
+            self.state.set(st);
+            Some(Action::Redraw())
+        } else {
+            None
+        }
+    }
+
+    /// Get the style group
+    pub(crate) fn style_group(&self) -> StyleGroup {
+        match self.state.get() {
+            State::Disabled => StyleGroup::Disabled,
+            State::Enabled => StyleGroup::Enabled,
+            State::Focused => StyleGroup::Focused,
+            State::Hovered => StyleGroup::Hovered,
+            State::Pressed => StyleGroup::Interacted,
+        }
+    }
+
+    /// Handle focus event
+    pub(crate) fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        use FocusEvent::*;
+        use State::*;
+        let state = self.state.get();
+        match (fev, state) {
+            (_, Disabled) => Some(Disabled),
+            (Offer, _) => Some(Focused),
+            (Take, _) => Some(Enabled),
+            (HoverInside, Enabled) => Some(Hovered),
+            (HoverInside, Pressed) => Some(Focused),
+            (HoverOutside, Hovered) => Some(Enabled),
+            (HoverOutside, Pressed) => Some(Focused),
+            _ => None,
+        }
+        .and_then(|st| self.set(st))
+    }
+
+    /// Handle mouse events
+    pub(crate) fn mouse_event(&self, mev: MouseEvent) -> Option<Action> {
+        match (mev, self.state.get()) {
+            (_, State::Disabled) => None,
+            (MouseEvent::ButtonDown(_), _) => self.set(State::Pressed),
+            _ => None,
+        }
+    }
+}
+
+/// Click a button-like widget, returning the action on release
+#[cfg(test)]
+pub(crate) fn click(widget: &dyn Widget) -> Option<Action> {
+    let dim = Dim::new(8, 1);
+    let pos = Pos::default();
+    let down = MouseEvent::ButtonDown(MouseButton::Left);
+    let up = MouseEvent::ButtonUp(MouseButton::Left);
+    widget.focus(FocusEvent::Offer);
+    widget.mouse_event(down, ModKeys::Empty, dim, pos);
+    widget.focus(FocusEvent::HoverInside);
+    widget.mouse_event(up, ModKeys::Empty, dim, pos)
+}
+
 /// Button widget
 pub struct Button<W: Widget> {
     /// Wrapped widget
     wrapped: W,
     /// Button state
-    state: Cell<State>,
+    state: ButtonState,
 }
 
 impl<W: Widget> Button<W> {
     /// Create a new button widget
     pub fn new(wrapped: W) -> Self {
-        let state = Cell::new(State::Enabled);
+        let state = ButtonState::new();
         Self { wrapped, state }
     }
 
     /// Disable the button
     pub fn disable(&self) {
-        self.state.set(State::Disabled);
+        self.state.disable();
     }
 
     /// Enable the button
     pub fn enable(&self) {
-        if self.state.get() == State::Disabled {
-            self.state.set(State::Enabled);
-        }
+        self.state.enable();
     }
 }
 
@@ -59,13 +163,7 @@ impl<W: Widget> Widget for Button<W> {
 
     /// Get the style group
     fn style_group(&self) -> StyleGroup {
-        match self.state.get() {
-            State::Disabled => StyleGroup::Disabled,
-            State::Enabled => StyleGroup::Enabled,
-            State::Focused => StyleGroup::Focused,
-            State::Hovered => StyleGroup::Hovered,
-            State::Pressed => StyleGroup::Interacted,
-        }
+        self.state.style_group()
     }
 
     /// Get the width bounds
@@ -87,27 +185,7 @@ impl<W: Widget> Widget for Button<W> {
 
     /// Handle focus event
     fn focus(&self, fev: FocusEvent) -> Option<Action> {
-        use FocusEvent::*;
-        use State::*;
-        let state = self.state.get();
-        match (fev, state) {
-            (_, Disabled) => Some(Disabled),
-            (Offer, _) => Some(Focused),
-            (Take, _) => Some(Enabled),
-            (HoverInside, Enabled) => Some(Hovered),
-            (HoverInside, Pressed) => Some(Focused),
-            (HoverOutside, Hovered) => Some(Enabled),
-            (HoverOutside, Pressed) => Some(Focused),
-            _ => None,
-        }
-        .and_then(|st| {
-            if st != self.state.get() {
-                self.state.set(st);
-                Some(Action::Redraw())
-            } else {
-                None
-            }
-        })
+        self.state.focus(fev)
     }
 
     /// Handle mouse events
@@ -118,19 +196,16 @@ impl<W: Widget> Widget for Button<W> {
         _dim: Dim,
         _pos: Pos,
     ) -> Option<Action> {
-        let state = self.state.get();
-        match (mev, state) {
-            (_, State::Disabled) => None,
-            (MouseEvent::ButtonDown(_), _) => Some(State::Pressed),
-            _ => None,
-        }
-        .and_then(|s| {
-            if s != state {
-                self.state.set(s);
-                Some(Action::Redraw())
-            } else {
-                None
-            }
-        })
+        self.state.mouse_event(mev)
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        self.wrapped.key_event(key, mods)
+    }
+
+    /// Get the text cursor position
+    fn cursor(&self) -> Option<Pos> {
+        self.wrapped.cursor()
     }
 }
@@ -0,0 +1,214 @@
+// checkbox.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent, NavKey};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{StyleGroup, Theme};
+use crate::widget::ButtonState;
+use crate::{Result, Widget};
+use std::cell::Cell;
+use unicode_width::UnicodeWidthStr;
+
+/// Check box state
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CheckState {
+    /// Not checked
+    Unchecked,
+    /// Checked
+    Checked,
+    /// Neither checked nor unchecked (tri-state)
+    Indeterminate,
+}
+
+/// Check box widget
+///
+/// Clicking, or pressing `Space` / `Enter` while focused, changes the state
+/// and produces an `Action::Checked`.
+pub struct CheckBox {
+    /// Label text
+    label: String,
+    /// Button state
+    button: ButtonState,
+    /// Check state
+    check: Cell<CheckState>,
+    /// Allow cycling through indeterminate state
+    tri_state: bool,
+}
+
+impl CheckState {
+    /// Get the indicator text
+    fn indicator(self) -> &'static str {
+        match self {
+            CheckState::Unchecked => "[ ]",
+            CheckState::Checked => "[x]",
+            CheckState::Indeterminate => "[-]",
+        }
+    }
+}
+
+impl CheckBox {
+    /// Create a new check box widget
+    pub fn new(label: &str) -> Self {
+        let label = label.to_string();
+        let button = ButtonState::new();
+        let check = Cell::new(CheckState::Unchecked);
+        let tri_state = false;
+        Self {
+            label,
+            button,
+            check,
+            tri_state,
+        }
+    }
+
+    /// Set the initial check state
+    pub fn with_state(self, check: CheckState) -> Self {
+        self.check.set(check);
+        self
+    }
+
+    /// Allow the user to select the indeterminate state
+    ///
+    /// Clicks cycle through unchecked, checked and indeterminate.  Otherwise,
+    /// indeterminate can only be set with [set_state].
+    ///
+    /// [set_state]: struct.CheckBox.html#method.set_state
+    pub fn with_tri_state(mut self, tri_state: bool) -> Self {
+        self.tri_state = tri_state;
+        self
+    }
+
+    /// Get the check state
+    pub fn state(&self) -> CheckState {
+        self.check.get()
+    }
+
+    /// Set the check state
+    pub fn set_state(&self, check: CheckState) {
+        self.check.set(check);
+    }
+
+    /// Check if checked
+    pub fn is_checked(&self) -> bool {
+        self.check.get() == CheckState::Checked
+    }
+
+    /// Disable the check box
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the check box
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Change to the next state
+    fn toggle(&self) -> Option<Action> {
+        use CheckState::*;
+        let check = match (self.check.get(), self.tri_state) {
+            (Unchecked, _) => Checked,
+            (Checked, true) => Indeterminate,
+            _ => Unchecked,
+        };
+        self.check.set(check);
+        Some(Action::Checked(check))
+    }
+}
+
+impl Widget for CheckBox {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        self.button.style_group()
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        let w = (self.label.width() as u16).saturating_add(4);
+        LengthBound::new(w..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        LengthBound::new(1..=1)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        if offset.row == 0 {
+            cells.move_to(0, 0)?;
+            cells.print_str(self.check.get().indicator())?;
+            cells.print_char(' ')?;
+            cells.print_str(&self.label)?;
+        }
+        Ok(())
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        _pos: Pos,
+    ) -> Option<Action> {
+        if self.button.is_clicked(mev) {
+            self.toggle()
+        } else {
+            self.button.mouse_event(mev)
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        match (key, mods) {
+            (KeyPress::Character(' '), ModKeys::Empty)
+            | (KeyPress::Navigation(NavKey::Enter), ModKeys::Empty)
+                if self.button.is_focused() =>
+            {
+                self.toggle()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widget::button::click;
+
+    #[test]
+    fn check() {
+        let cb = CheckBox::new("Check");
+        assert_eq!(click(&cb), Some(Action::Checked(CheckState::Checked)));
+        assert_eq!(click(&cb), Some(Action::Checked(CheckState::Unchecked)));
+        let space = KeyPress::Character(' ');
+        assert_eq!(
+            cb.key_event(space, ModKeys::Empty),
+            Some(Action::Checked(CheckState::Checked))
+        );
+        cb.focus(FocusEvent::Take);
+        assert_eq!(cb.key_event(space, ModKeys::Empty), None);
+        cb.disable();
+        assert_eq!(click(&cb), None);
+        assert!(cb.is_checked());
+    }
+
+    #[test]
+    fn tri_state() {
+        let cb = CheckBox::new("Tri").with_tri_state(true);
+        assert_eq!(click(&cb), Some(Action::Checked(CheckState::Checked)));
+        assert_eq!(
+            click(&cb),
+            Some(Action::Checked(CheckState::Indeterminate))
+        );
+        assert_eq!(click(&cb), Some(Action::Checked(CheckState::Unchecked)));
+    }
+}
@@ -0,0 +1,257 @@
+// codeview.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::layout::{Cells, LengthBound, Pos};
+use crate::text::{
+    line_width, push_span, sanitize, skip_columns, Span, StyleGroup, Syntax,
+    Theme, TokenKind, Tokenizer,
+};
+use crate::{Result, Widget};
+use unicode_width::UnicodeWidthStr;
+
+/// Source code view widget
+///
+/// Code is displayed with syntax highlighting, using [StyleGroup::Token]
+/// styles from the [Theme].  Lines are not wrapped, so a [ScrollView] with
+/// horizontal scroll bars can be used for wide code.
+///
+/// ```rust
+/// use semtext::text::Syntax;
+/// use semtext::widget::CodeView;
+///
+/// let code = CodeView::new("[package]\nname = \"semtext\"")
+///     .with_syntax(Syntax::Toml)
+///     .with_line_numbers(true);
+/// ```
+///
+/// [ScrollView]: struct.ScrollView.html
+/// [StyleGroup::Token]: ../text/enum.StyleGroup.html#variant.Token
+/// [Theme]: ../text/struct.Theme.html
+pub struct CodeView {
+    /// Source code
+    code: String,
+    /// Highlighted lines
+    lines: Vec<Vec<Span>>,
+    /// Line numbers enabled
+    line_numbers: bool,
+}
+
+/// Push a token, splitting lines
+fn push_token(lines: &mut Vec<Vec<Span>>, kind: TokenKind, text: &str) {
+    let span = Span::new("").with_group(StyleGroup::Token(kind));
+    for (i, txt) in text.split('\n').enumerate() {
+        if i > 0 {
+            lines.push(vec![]);
+        }
+        if let Some(line) = lines.last_mut() {
+            push_span(line, span.with_text(txt));
+        }
+    }
+}
+
+/// Highlight source code into lines of spans
+fn highlight<T: Tokenizer>(code: &str, tokenizer: &T) -> Vec<Vec<Span>> {
+    let code = code.strip_suffix('\n').unwrap_or(code);
+    let mut lines = vec![vec![]];
+    let mut pos = 0;
+    for token in tokenizer.tokenize(code) {
+        // Clamp overlapping tokens, and skip invalid ranges
+        let start = token.range.start.max(pos);
+        let text = match code.get(start..token.range.end) {
+            Some(text) if !text.is_empty() => text,
+            _ => continue,
+        };
+        if start > pos {
+            push_token(&mut lines, TokenKind::Plain, &code[pos..start]);
+        }
+        push_token(&mut lines, token.kind, text);
+        pos = token.range.end;
+    }
+    if pos < code.len() {
+        push_token(&mut lines, TokenKind::Plain, &code[pos..]);
+    }
+    // Trim carriage returns from CRLF line endings
+    for line in lines.iter_mut() {
+        if let Some(last) = line.pop() {
+            match last.text().strip_suffix('\r') {
+                Some(text) => push_span(line, last.with_text(text)),
+                None => line.push(last),
+            }
+        }
+    }
+    lines
+}
+
+impl CodeView {
+    /// Create a new code view widget
+    ///
+    /// No syntax highlighting is done, unless [with_syntax] is used.
+    ///
+    /// [with_syntax]: struct.CodeView.html#method.with_syntax
+    pub fn new(code: &str) -> Self {
+        let lines = highlight(code, &Syntax::Plain);
+        Self {
+            code: code.to_string(),
+            lines,
+            line_numbers: false,
+        }
+    }
+
+    /// Set the syntax tokenizer
+    ///
+    /// - `tokenizer`: A built-in [Syntax] or custom [Tokenizer]
+    ///
+    /// [Syntax]: ../text/enum.Syntax.html
+    /// [Tokenizer]: ../text/trait.Tokenizer.html
+    pub fn with_syntax<T: Tokenizer>(mut self, tokenizer: T) -> Self {
+        self.lines = highlight(&self.code, &tokenizer);
+        self
+    }
+
+    /// Enable or disable line numbers
+    pub fn with_line_numbers(mut self, enable: bool) -> Self {
+        self.line_numbers = enable;
+        self
+    }
+
+    /// Get the source code
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Get the width of the line number gutter
+    fn gutter_width(&self) -> usize {
+        if self.line_numbers {
+            self.lines.len().to_string().len() + 1
+        } else {
+            0
+        }
+    }
+
+    /// Expand tabs and replace control characters in a line
+    fn expand_line(line: &[Span], theme: &Theme) -> Vec<Span> {
+        let mut col = 0;
+        let mut expanded = vec![];
+        for span in line {
+            let text =
+                sanitize(span.text(), col, theme.tab_stop, theme.control_chars);
+            col = col.saturating_add(text.width() as u16);
+            expanded.push(span.with_text(&text));
+        }
+        expanded
+    }
+}
+
+impl Widget for CodeView {
+    /// Get the width bounds
+    fn width_bounds(&self, theme: &Theme) -> LengthBound {
+        let w = self
+            .lines
+            .iter()
+            .map(|line| line_width(&Self::expand_line(line, theme)))
+            .max()
+            .unwrap_or(0);
+        let w = (self.gutter_width() + w).min(usize::from(u16::MAX)) as u16;
+        LengthBound::new(w..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        let rows = self.lines.len().min(usize::from(u16::MAX)) as u16;
+        LengthBound::new(rows..=rows)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let top = usize::from(offset.row);
+        let height = usize::from(cells.height());
+        let gutter = self.gutter_width();
+        for (row, line) in self.lines.iter().skip(top).take(height).enumerate()
+        {
+            let row = row as u16; // limited to u16 by take(height)
+            cells.move_to(0, row)?;
+            if self.line_numbers {
+                let num = format!(
+                    "{:>w$} ",
+                    top + 1 + usize::from(row),
+                    w = gutter - 1
+                );
+                let num = Span::new(&num).with_group(StyleGroup::Disabled);
+                cells.print_span(&num)?;
+            }
+            let line = Self::expand_line(line, cells.theme());
+            for span in skip_columns(&line, usize::from(offset.col)) {
+                cells.print_span(&span)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::Token;
+
+    #[test]
+    fn lines() {
+        let code = "a = 1\r\n\nb = '''x\ny'''\n";
+        let lines = highlight(code, &Syntax::Toml);
+        let texts: Vec<Vec<&str>> = lines
+            .iter()
+            .map(|l| l.iter().map(Span::text).collect())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                vec!["a", " ", "=", " ", "1"],
+                vec![],
+                vec!["b", " ", "=", " ", "'''x"],
+                vec!["y'''"],
+            ]
+        );
+        assert_eq!(lines[3][0].group(), StyleGroup::Token(TokenKind::String));
+    }
+
+    /// Tokenizer producing invalid ranges
+    struct Invalid;
+
+    impl Tokenizer for Invalid {
+        fn tokenize(&self, _code: &str) -> Vec<Token> {
+            vec![
+                Token {
+                    kind: TokenKind::Keyword,
+                    range: 0..2,
+                },
+                Token {
+                    kind: TokenKind::Number,
+                    range: 1..3,
+                },
+                Token {
+                    kind: TokenKind::String,
+                    range: 4..5,
+                },
+                Token {
+                    kind: TokenKind::Comment,
+                    range: 6..99,
+                },
+            ]
+        }
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let lines = highlight("ab cé d", &Invalid);
+        let texts: Vec<&str> = lines[0].iter().map(Span::text).collect();
+        assert_eq!(texts, vec!["ab", " ", "cé d"]);
+    }
+
+    #[test]
+    fn bounds() {
+        let theme = Theme::default();
+        let code = CodeView::new("one\n\ttwo").with_line_numbers(true);
+        assert_eq!(code.width_bounds(&theme), LengthBound::new(13..));
+        assert_eq!(code.height_bounds(&theme, 20), LengthBound::new(2..=2));
+    }
+}
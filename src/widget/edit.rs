@@ -0,0 +1,415 @@
+// edit.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::text::grapheme_width;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maximum number of undo steps
+const UNDO_LIMIT: usize = 256;
+
+/// Kind of edit, for grouping undo steps
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum EditKind {
+    /// Insert text (not containing whitespace)
+    Insert,
+    /// Delete before cursor
+    DeleteBack,
+    /// Delete after cursor
+    DeleteForward,
+    /// Any other edit (never grouped)
+    Other,
+}
+
+/// Snapshot of text and cursor, for undo / redo
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Snapshot {
+    /// Text
+    text: String,
+    /// Cursor offset
+    cursor: usize,
+}
+
+/// Editable text buffer
+///
+/// Cursor and selection anchor are byte offsets, always on grapheme cluster
+/// boundaries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct EditBuffer {
+    /// Text being edited
+    text: String,
+    /// Cursor offset
+    cursor: usize,
+    /// Selection anchor offset
+    anchor: Option<usize>,
+    /// Undo history (oldest first)
+    undo: Vec<Snapshot>,
+    /// Redo history (newest first)
+    redo: Vec<Snapshot>,
+    /// Kind of last edit, if it can be grouped
+    last_edit: Option<EditKind>,
+}
+
+/// Check if a word segment contains word characters
+fn is_word(seg: &str) -> bool {
+    seg.chars().any(char::is_alphanumeric)
+}
+
+impl EditBuffer {
+    /// Create a new edit buffer, with cursor at the end
+    pub(crate) fn new(text: &str) -> Self {
+        let text = text.to_string();
+        let cursor = text.len();
+        let anchor = None;
+        Self {
+            text,
+            cursor,
+            anchor,
+            ..Default::default()
+        }
+    }
+
+    /// Get the text
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, moving the cursor to the end
+    pub(crate) fn set_text(&mut self, text: &str) {
+        *self = Self::new(text);
+    }
+
+    /// Get the cursor offset
+    pub(crate) fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Get the selected range, if any
+    pub(crate) fn selection(&self) -> Option<Range<usize>> {
+        match self.anchor {
+            Some(anchor) if anchor < self.cursor => Some(anchor..self.cursor),
+            Some(anchor) if anchor > self.cursor => Some(self.cursor..anchor),
+            _ => None,
+        }
+    }
+
+    /// Get the selected text
+    pub(crate) fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|sel| &self.text[sel])
+    }
+
+    /// Move the cursor
+    ///
+    /// * `offset`: New cursor offset
+    /// * `extend`: Extend the selection (otherwise, it is cleared)
+    pub(crate) fn move_to(&mut self, offset: usize, extend: bool) {
+        if extend {
+            self.anchor = self.anchor.or(Some(self.cursor));
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = offset.min(self.text.len());
+        self.last_edit = None;
+    }
+
+    /// Take a snapshot of the text and cursor
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.clone(),
+            cursor: self.cursor,
+        }
+    }
+
+    /// Restore a snapshot
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.text = snapshot.text;
+        self.cursor = snapshot.cursor;
+        self.anchor = None;
+        self.last_edit = None;
+    }
+
+    /// Record an undo step before an edit
+    ///
+    /// Consecutive edits of the same kind are grouped into one step.
+    fn checkpoint(&mut self, kind: EditKind) {
+        if kind == EditKind::Other || self.last_edit != Some(kind) {
+            if self.undo.len() >= UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+            self.undo.push(self.snapshot());
+        }
+        self.redo.clear();
+        self.last_edit = Some(kind).filter(|k| *k != EditKind::Other);
+    }
+
+    /// Undo the last edit, returning true if anything was undone
+    pub(crate) fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(snapshot) => {
+                self.redo.push(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone edit, returning true if anything was redone
+    pub(crate) fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(snapshot) => {
+                self.undo.push(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Select all text
+    pub(crate) fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.text.len();
+    }
+
+    /// Get the offset of the previous grapheme cluster
+    pub(crate) fn prev_grapheme(&self, offset: usize) -> usize {
+        self.text[..offset]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Get the offset of the next grapheme cluster
+    pub(crate) fn next_grapheme(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .graphemes(true)
+            .next()
+            .map_or(offset, |g| offset + g.len())
+    }
+
+    /// Get the offset of the start of the previous word
+    pub(crate) fn prev_word(&self, offset: usize) -> usize {
+        self.text[..offset]
+            .split_word_bound_indices()
+            .rev()
+            .find(|(_, seg)| is_word(seg))
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Get the offset of the end of the next word
+    pub(crate) fn next_word(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .split_word_bound_indices()
+            .find(|(_, seg)| is_word(seg))
+            .map_or(self.text.len(), |(i, seg)| offset + i + seg.len())
+    }
+
+    /// Delete the selection, returning true if anything was deleted
+    pub(crate) fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some(sel) => {
+                self.checkpoint(EditKind::Other);
+                self.cursor = sel.start;
+                self.anchor = None;
+                self.text.replace_range(sel, "");
+                true
+            }
+            None => {
+                self.anchor = None;
+                false
+            }
+        }
+    }
+
+    /// Delete a range before or after the cursor
+    fn delete_range(&mut self, range: Range<usize>) -> bool {
+        if range.is_empty() {
+            return false;
+        }
+        let kind = if range.end == self.cursor {
+            EditKind::DeleteBack
+        } else {
+            EditKind::DeleteForward
+        };
+        self.checkpoint(kind);
+        self.cursor = range.start;
+        self.text.replace_range(range, "");
+        true
+    }
+
+    /// Insert text at the cursor, replacing the selection
+    pub(crate) fn insert(&mut self, text: &str) {
+        if !self.delete_selection() {
+            if text.contains(char::is_whitespace) {
+                self.checkpoint(EditKind::Other);
+            } else {
+                self.checkpoint(EditKind::Insert);
+            }
+        }
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// Delete the selection or the grapheme cluster before the cursor
+    pub(crate) fn delete_back(&mut self) -> bool {
+        self.delete_selection()
+            || self.delete_range(self.prev_grapheme(self.cursor)..self.cursor)
+    }
+
+    /// Delete the selection or the grapheme cluster after the cursor
+    pub(crate) fn delete_forward(&mut self) -> bool {
+        self.delete_selection()
+            || self.delete_range(self.cursor..self.next_grapheme(self.cursor))
+    }
+
+    /// Delete the selection or the word before the cursor
+    pub(crate) fn delete_word_back(&mut self) -> bool {
+        self.delete_selection()
+            || self.delete_range(self.prev_word(self.cursor)..self.cursor)
+    }
+
+    /// Delete the selection or the word after the cursor
+    pub(crate) fn delete_word_forward(&mut self) -> bool {
+        self.delete_selection()
+            || self.delete_range(self.cursor..self.next_word(self.cursor))
+    }
+}
+
+/// Get the visible grapheme clusters of a line of text
+///
+/// * `text`: Line of text
+/// * `scroll`: Number of columns scrolled off the left edge
+/// * `width`: Visible width in text cells
+/// * `shown`: Function to get the text shown for a grapheme cluster
+///
+/// ## Return
+///
+/// Byte offset, shown text and number of columns cut off the left edge of
+/// each visible grapheme cluster.  Only a wide cluster which straddles the
+/// left edge is cut off.
+pub(crate) fn visible_graphemes<'a, F>(
+    text: &'a str,
+    scroll: u16,
+    width: u16,
+    shown: F,
+) -> Vec<(usize, &'a str, u16)>
+where
+    F: Fn(&'a str) -> &'a str,
+{
+    let end = scroll.saturating_add(width);
+    let mut visible = vec![];
+    let mut col = 0;
+    for (i, g) in text.grapheme_indices(true) {
+        if col >= end {
+            break;
+        }
+        let g = shown(g);
+        let w = grapheme_width(g) as u16;
+        if col + w > scroll {
+            visible.push((i, g, scroll.saturating_sub(col)));
+        }
+        col += w;
+    }
+    visible
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn visible() {
+        let same = |g| g;
+        let text = "a🦀b中文";
+        assert_eq!(
+            visible_graphemes(text, 0, 3, same),
+            [(0, "a", 0), (1, "🦀", 0)]
+        );
+        assert_eq!(
+            visible_graphemes(text, 2, 3, same),
+            [(1, "🦀", 1), (5, "b", 0), (6, "中", 0)]
+        );
+        assert_eq!(
+            visible_graphemes(text, 5, 2, same),
+            [(6, "中", 1), (9, "文", 0)]
+        );
+        assert_eq!(visible_graphemes(text, 7, 2, same), [(9, "文", 1)]);
+        assert_eq!(visible_graphemes(text, 1, 1, |_| "*"), [(1, "*", 0)]);
+    }
+
+    #[test]
+    fn graphemes() {
+        let mut buf = EditBuffer::new("a\u{308}bc");
+        assert_eq!(buf.prev_grapheme(buf.cursor()), 4);
+        assert_eq!(buf.prev_grapheme(3), 0);
+        assert_eq!(buf.next_grapheme(0), 3);
+        buf.move_to(3, false);
+        assert!(buf.delete_back());
+        assert_eq!(buf.text(), "bc");
+        assert!(!buf.delete_back());
+        buf.insert("🦀");
+        assert_eq!(buf.text(), "🦀bc");
+        assert!(buf.delete_forward());
+        assert_eq!(buf.text(), "🦀c");
+    }
+
+    #[test]
+    fn words() {
+        let mut buf = EditBuffer::new("one two, three");
+        assert_eq!(buf.prev_word(buf.cursor()), 9);
+        assert_eq!(buf.prev_word(9), 4);
+        assert_eq!(buf.prev_word(2), 0);
+        assert_eq!(buf.next_word(0), 3);
+        assert_eq!(buf.next_word(3), 7);
+        assert_eq!(buf.next_word(7), 14);
+        assert!(buf.delete_word_back());
+        assert_eq!(buf.text(), "one two, ");
+        buf.move_to(0, false);
+        assert!(buf.delete_word_forward());
+        assert_eq!(buf.text(), " two, ");
+    }
+
+    #[test]
+    fn selection() {
+        let mut buf = EditBuffer::new("hello world");
+        buf.move_to(6, true);
+        assert_eq!(buf.selected_text(), Some("world"));
+        buf.insert("there");
+        assert_eq!(buf.text(), "hello there");
+        assert_eq!(buf.selection(), None);
+        buf.select_all();
+        assert!(buf.delete_back());
+        assert_eq!(buf.text(), "");
+    }
+
+    #[test]
+    fn undo() {
+        let mut buf = EditBuffer::new("");
+        for ch in &["a", "b", " ", "c", "d"] {
+            buf.insert(ch);
+        }
+        buf.delete_back();
+        buf.delete_back();
+        assert_eq!(buf.text(), "ab ");
+        assert!(buf.undo());
+        assert_eq!(buf.text(), "ab cd");
+        assert!(buf.undo());
+        assert_eq!(buf.text(), "ab ");
+        assert!(buf.undo());
+        assert_eq!(buf.text(), "ab");
+        assert!(buf.redo());
+        assert!(buf.redo());
+        assert_eq!(buf.text(), "ab cd");
+        assert_eq!(buf.cursor(), 5);
+        buf.insert("e");
+        assert!(!buf.redo());
+        assert!(buf.undo());
+        assert!(buf.undo());
+        assert!(buf.undo());
+        assert!(buf.undo());
+        assert_eq!(buf.text(), "");
+        assert!(!buf.undo());
+    }
+}
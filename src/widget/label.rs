@@ -2,8 +2,9 @@
 //
 // Copyright (c) 2020-2021  Douglas P Lau
 //
-use crate::layout::{Cells, LengthBound, Pos};
-use crate::text::Theme;
+use crate::input::{Action, ModKeys, MouseButton, MouseEvent};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{sanitize_lines, Align, StyleGroup, Theme};
 use crate::{Result, Widget};
 use textwrap::wrap;
 use unicode_width::UnicodeWidthStr;
@@ -12,13 +13,51 @@ use unicode_width::UnicodeWidthStr;
 pub struct Label {
     /// Text of label
     text: String,
+    /// Text alignment
+    align: Align,
+    /// Style group
+    group: StyleGroup,
+    /// Hyperlink target
+    link: Option<String>,
 }
 
 impl Label {
     /// Create a new label widget
     pub fn new(text: &str) -> Self {
         let text = text.to_string();
-        Label { text }
+        let align = Align::default();
+        let group = StyleGroup::Enabled;
+        let link = None;
+        Label {
+            text,
+            align,
+            group,
+            link,
+        }
+    }
+
+    /// Set the text alignment
+    ///
+    /// By default, text is aligned to the start of its paragraph direction.
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the style group
+    ///
+    /// This can be used for semantic styles, such as `StyleGroup::Error`.
+    pub fn with_style_group(mut self, group: StyleGroup) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Set the hyperlink target (such as a URL)
+    ///
+    /// When clicked, the label produces an `Action::Link`.
+    pub fn with_link(mut self, link: &str) -> Self {
+        self.link = Some(link.to_string());
+        self
     }
 
     /// Get label text
@@ -28,9 +67,16 @@ impl Label {
 }
 
 impl Widget for Label {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        self.group
+    }
+
     /// Get the width bounds
-    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
-        let w = self.text.width() as u16;
+    fn width_bounds(&self, theme: &Theme) -> LengthBound {
+        let text =
+            sanitize_lines(&self.text, theme.tab_stop, theme.control_chars);
+        let w = text.width() as u16;
         match w {
             0..=8 => LengthBound::new(w..),
             9..=20 => LengthBound::new(10..),
@@ -39,13 +85,49 @@ impl Widget for Label {
     }
 
     /// Get the height bounds
-    fn height_bounds(&self, _theme: &Theme, width: u16) -> LengthBound {
-        let rows = wrap(&self.text, usize::from(width)).iter().count() as u16;
+    fn height_bounds(&self, theme: &Theme, width: u16) -> LengthBound {
+        let text =
+            sanitize_lines(&self.text, theme.tab_stop, theme.control_chars);
+        let rows = wrap(&text, usize::from(width)).iter().count() as u16;
         LengthBound::new(rows..=rows)
     }
 
     /// Draw the widget
     fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
-        cells.print_text(&self.text, offset)
+        cells.set_link(self.link.as_deref())?;
+        cells.print_text_aligned(&self.text, offset, self.align)?;
+        cells.set_link(None)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        _pos: Pos,
+    ) -> Option<Action> {
+        match (mev, &self.link) {
+            (MouseEvent::ButtonUp(MouseButton::Left), Some(link)) => {
+                Some(Action::Link(link.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn link() {
+        let click = MouseEvent::ButtonUp(MouseButton::Left);
+        let (mods, dim, pos) = (ModKeys::Empty, Dim::new(4, 1), Pos::new(1, 0));
+        let label = Label::new("docs");
+        assert_eq!(label.mouse_event(click, mods, dim, pos), None);
+        let label = label.with_link("https://docs.rs");
+        let action = Some(Action::Link("https://docs.rs".to_string()));
+        assert_eq!(label.mouse_event(click, mods, dim, pos), action);
     }
 }
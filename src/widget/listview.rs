@@ -0,0 +1,599 @@
+// listview.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent, NavKey,
+};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{StyleGroup, Theme};
+use crate::widget::ButtonState;
+use crate::{Result, Widget};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+/// Time after last key press to start a new type-ahead search
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Source of list items
+///
+/// Items are only requested when needed, such as for visible rows.
+pub trait ListSource {
+    /// Get the number of items
+    fn len(&self) -> usize;
+
+    /// Check if there are no items
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the text of an item
+    fn item(&self, index: usize) -> Cow<'_, str>;
+}
+
+impl<T: AsRef<str>> ListSource for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn item(&self, index: usize) -> Cow<'_, str> {
+        Cow::Borrowed(self[index].as_ref())
+    }
+}
+
+/// List selection mode
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SelectionMode {
+    /// One item selected, following the cursor
+    Single,
+    /// Any number of items selected
+    Multiple,
+}
+
+/// Get the item index after a navigation key
+///
+/// * `nav`: Navigation key
+/// * `index`: Current index
+/// * `len`: Number of items
+/// * `page`: Number of visible rows
+pub(crate) fn nav_index(
+    nav: NavKey,
+    index: usize,
+    len: usize,
+    page: usize,
+) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    let index = match nav {
+        NavKey::Up => index.saturating_sub(1),
+        NavKey::Down => index.saturating_add(1),
+        NavKey::PageUp => index.saturating_sub(page.max(1)),
+        NavKey::PageDown => index.saturating_add(page.max(1)),
+        NavKey::Home => 0,
+        NavKey::End => last,
+        _ => return None,
+    };
+    Some(index.min(last))
+}
+
+/// Get the style group of a list-like widget
+///
+/// Only the disabled state is shown; rows are styled individually.
+pub(crate) fn list_style_group(button: &ButtonState) -> StyleGroup {
+    match button.style_group() {
+        StyleGroup::Disabled => StyleGroup::Disabled,
+        _ => StyleGroup::Enabled,
+    }
+}
+
+/// Fill the remainder of a row with spaces, to highlight it
+///
+/// * `used`: Width already printed on the row
+pub(crate) fn fill_row(cells: &mut Cells, used: usize) -> Result<()> {
+    let pad = usize::from(cells.width()).saturating_sub(used);
+    cells.print_str(&" ".repeat(pad))
+}
+
+/// Row scrolling state, for widgets which draw only visible rows
+///
+/// The top row follows the scroll offset when it changes (scrolling), and
+/// the selected row when it changes (navigation).
+#[derive(Default)]
+pub(crate) struct RowScroll {
+    /// Row at top (from last draw)
+    top: Cell<usize>,
+    /// Visible rows (from last draw)
+    rows: Cell<u16>,
+    /// Scroll offset (from last draw)
+    offset: Cell<Pos>,
+    /// Selected row (from last draw)
+    drawn: Cell<Option<usize>>,
+}
+
+impl RowScroll {
+    /// Get the row at top
+    pub(crate) fn top(&self) -> usize {
+        self.top.get()
+    }
+
+    /// Set the row at top
+    pub(crate) fn set_top(&self, top: usize) {
+        self.top.set(top);
+    }
+
+    /// Get the number of visible rows
+    pub(crate) fn rows(&self) -> u16 {
+        self.rows.get()
+    }
+
+    /// Get the scroll offset
+    pub(crate) fn offset(&self) -> Pos {
+        self.offset.get()
+    }
+
+    /// Get the row at a widget position
+    pub(crate) fn row_at(&self, pos: Pos) -> usize {
+        self.top() + usize::from(pos.row.saturating_sub(self.offset.get().row))
+    }
+
+    /// Get the range of visible rows for a draw
+    ///
+    /// * `offset`: Scroll offset
+    /// * `rows`: Number of visible rows
+    /// * `len`: Total number of rows
+    /// * `selected`: Selected row
+    /// * `row_index`: Get the row at the top of a scroll offset row
+    pub(crate) fn visible<F>(
+        &self,
+        offset: Pos,
+        rows: u16,
+        len: usize,
+        selected: Option<usize>,
+        row_index: F,
+    ) -> Range<usize>
+    where
+        F: Fn(u16) -> usize,
+    {
+        let mut top = self.top.get();
+        if offset != self.offset.get() || rows != self.rows.get() {
+            top = row_index(offset.row);
+        }
+        if selected != self.drawn.get() {
+            if let Some(sel) = selected {
+                let rows = usize::from(rows.max(1));
+                if sel < top {
+                    top = sel;
+                } else if sel >= top + rows {
+                    top = sel + 1 - rows;
+                }
+            }
+        }
+        let top = top.min(len.saturating_sub(usize::from(rows)));
+        self.top.set(top);
+        self.rows.set(rows);
+        self.offset.set(offset);
+        self.drawn.set(selected);
+        top..len.min(top + usize::from(rows))
+    }
+}
+
+/// Selectable list widget
+///
+/// While focused, keys move the cursor:
+///
+/// Key                        | Action
+/// ---------------------------|-------
+/// `Up` / `Down`              | Move by one item
+/// `PageUp` / `PageDown`      | Move by visible rows
+/// `Home` / `End`             | Move to first / last item
+/// `Shift` + movement         | Extend selection (multiple)
+/// `Space`                    | Toggle item selection (multiple)
+/// `Ctrl` + `A`               | Select all (multiple)
+/// Characters                 | Move to next item starting with typed text
+///
+/// Items can also be selected with the mouse; in multiple mode, `Ctrl` +
+/// click toggles an item, and `Shift` + click selects a range.
+///
+/// In single mode, changes produce `Action::Selected`; in multiple mode,
+/// `Action::SelectionChanged`.  Selected items are drawn with the
+/// `StyleGroup::Focused` style.
+pub struct ListView<S: ListSource> {
+    /// Item source
+    source: S,
+    /// Selection mode
+    mode: SelectionMode,
+    /// Button state
+    button: ButtonState,
+    /// Cursor item index
+    cursor: Cell<usize>,
+    /// Selected item indices
+    selected: RefCell<BTreeSet<usize>>,
+    /// Row scrolling state
+    scroll: RowScroll,
+    /// Type-ahead search text
+    search: RefCell<String>,
+    /// Time of last type-ahead key press
+    search_time: Cell<Option<Instant>>,
+}
+
+impl<S: ListSource> ListView<S> {
+    /// Create a new list view widget
+    pub fn new(source: S) -> Self {
+        let mode = SelectionMode::Single;
+        let button = ButtonState::new();
+        let cursor = Cell::new(0);
+        let selected = RefCell::new(BTreeSet::new());
+        let scroll = RowScroll::default();
+        let search = RefCell::new(String::new());
+        let search_time = Cell::new(None);
+        Self {
+            source,
+            mode,
+            button,
+            cursor,
+            selected,
+            scroll,
+            search,
+            search_time,
+        }
+    }
+
+    /// Set the selection mode (single by default)
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the item source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get the selected item index (first, in multiple mode)
+    pub fn selected(&self) -> Option<usize> {
+        self.selected.borrow().iter().next().copied()
+    }
+
+    /// Get all selected item indices
+    pub fn selected_all(&self) -> Vec<usize> {
+        self.selected.borrow().iter().copied().collect()
+    }
+
+    /// Select an item, moving the cursor to it
+    ///
+    /// In single mode, any other selection is cleared.
+    pub fn set_selected(&self, index: usize) {
+        if index < self.source.len() {
+            if self.mode == SelectionMode::Single {
+                self.selected.borrow_mut().clear();
+            }
+            self.selected.borrow_mut().insert(index);
+            self.cursor.set(index);
+        }
+    }
+
+    /// Clear the selection
+    pub fn clear_selection(&self) {
+        self.selected.borrow_mut().clear();
+    }
+
+    /// Disable the list
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the list
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Get the range of visible items for a scroll offset
+    fn visible(&self, offset: Pos, rows: u16) -> Range<usize> {
+        self.scroll.visible(
+            offset,
+            rows,
+            self.source.len(),
+            Some(self.cursor.get()),
+            usize::from,
+        )
+    }
+
+    /// Get the action for a changed selection
+    fn selection_action(&self) -> Option<Action> {
+        match self.mode {
+            SelectionMode::Single => self.selected().map(Action::Selected),
+            SelectionMode::Multiple => {
+                Some(Action::SelectionChanged(self.selected_all()))
+            }
+        }
+    }
+
+    /// Move the cursor to an item, updating the selection
+    ///
+    /// * `index`: Item index
+    /// * `extend`: Extend the selection to the item (multiple mode)
+    fn go_to(&self, index: usize, extend: bool) -> Option<Action> {
+        let cursor = self.cursor.get();
+        self.cursor.set(index);
+        match self.mode {
+            SelectionMode::Single => {
+                if self.selected() == Some(index) {
+                    return Some(Action::Redraw());
+                }
+                self.set_selected(index);
+            }
+            SelectionMode::Multiple if extend => {
+                let range = cursor.min(index)..=cursor.max(index);
+                self.selected.borrow_mut().extend(range);
+            }
+            SelectionMode::Multiple => return Some(Action::Redraw()),
+        }
+        self.selection_action()
+    }
+
+    /// Toggle selection of an item (multiple mode)
+    fn toggle(&self, index: usize) -> Option<Action> {
+        self.cursor.set(index);
+        {
+            let mut selected = self.selected.borrow_mut();
+            if !selected.remove(&index) {
+                selected.insert(index);
+            }
+        }
+        self.selection_action()
+    }
+
+    /// Check if a type-ahead search was typed recently
+    fn is_searching(&self, now: Instant) -> bool {
+        self.search_time
+            .get()
+            .map_or(false, |t| now.duration_since(t) < TYPE_AHEAD_TIMEOUT)
+    }
+
+    /// Handle a type-ahead search character
+    fn type_ahead(&self, ch: char) -> Option<Action> {
+        let now = Instant::now();
+        let recent = self.is_searching(now);
+        self.search_time.set(Some(now));
+        let mut search = self.search.borrow_mut();
+        if !recent {
+            search.clear();
+        }
+        search.extend(ch.to_lowercase());
+        let len = self.source.len();
+        // A new search starts after the cursor
+        let start =
+            self.cursor.get() + usize::from(search.chars().count() == 1);
+        let found = (0..len).map(|i| (start + i) % len).find(|i| {
+            self.source.item(*i).to_lowercase().starts_with(&*search)
+        });
+        drop(search);
+        found.and_then(|i| self.go_to(i, false))
+    }
+}
+
+impl<S: ListSource> Widget for ListView<S> {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        list_style_group(&self.button)
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        let w = (0..self.source.len())
+            .map(|i| self.source.item(i).width())
+            .max()
+            .unwrap_or(0);
+        LengthBound::new(w as u16..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        let rows = self.source.len().min(usize::from(u16::MAX));
+        LengthBound::new(rows as u16..)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let visible = self.visible(offset, cells.height());
+        let top = visible.start;
+        let style = cells.style(self.style_group());
+        let selected_style = cells.style(StyleGroup::Focused);
+        let cursor_style = cells.style(StyleGroup::Hovered);
+        let focused = self.button.is_focused();
+        let selected = self.selected.borrow();
+        for index in visible {
+            let st = if selected.contains(&index) {
+                selected_style
+            } else if focused && index == self.cursor.get() {
+                cursor_style
+            } else {
+                style
+            };
+            let item = self.source.item(index);
+            cells.set_style(st)?;
+            cells.move_to(0, (index - top) as u16)?;
+            cells.print_str(&item)?;
+            fill_row(cells, item.width())?;
+        }
+        cells.set_style(style)
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        let redraw = self.button.mouse_event(mev);
+        let len = self.source.len();
+        let top = self.scroll.top();
+        match mev {
+            MouseEvent::ButtonDown(MouseButton::Left)
+                if self.button.is_focused() =>
+            {
+                let index = self.scroll.row_at(pos);
+                if index >= len {
+                    return redraw;
+                }
+                match (self.mode, mods) {
+                    (SelectionMode::Multiple, ModKeys::Control) => {
+                        self.toggle(index)
+                    }
+                    (SelectionMode::Multiple, ModKeys::Shift) => {
+                        self.go_to(index, true)
+                    }
+                    (SelectionMode::Multiple, _) => {
+                        self.selected.borrow_mut().clear();
+                        self.toggle(index)
+                    }
+                    _ => self.go_to(index, false),
+                }
+            }
+            // Only received when not wrapped in a scroll view
+            MouseEvent::ScrollUp() if top > 0 => {
+                self.scroll.set_top(top - 1);
+                Some(Action::Redraw())
+            }
+            MouseEvent::ScrollDown() if top + 1 < len => {
+                self.scroll.set_top(top + 1);
+                Some(Action::Redraw())
+            }
+            _ => redraw,
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if !self.button.is_focused() {
+            return None;
+        }
+        let multiple = self.mode == SelectionMode::Multiple;
+        match (key, mods) {
+            (KeyPress::Navigation(nav), ModKeys::Empty)
+            | (KeyPress::Navigation(nav), ModKeys::Shift) => {
+                let len = self.source.len();
+                let page = usize::from(self.scroll.rows());
+                let index = nav_index(nav, self.cursor.get(), len, page)?;
+                self.go_to(index, mods == ModKeys::Shift)
+            }
+            (KeyPress::Character(' '), ModKeys::Empty)
+                if multiple && !self.is_searching(Instant::now()) =>
+            {
+                if self.source.is_empty() {
+                    None
+                } else {
+                    self.toggle(self.cursor.get())
+                }
+            }
+            (KeyPress::Character('a'), ModKeys::Control) if multiple => {
+                self.selected.borrow_mut().extend(0..self.source.len());
+                self.selection_action()
+            }
+            (KeyPress::Character(ch), ModKeys::Empty)
+            | (KeyPress::Character(ch), ModKeys::Shift)
+                if !ch.is_control() =>
+            {
+                self.type_ahead(ch)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the row of the cursor
+    fn cursor(&self) -> Option<Pos> {
+        let row = self.cursor.get().min(usize::from(u16::MAX));
+        Some(Pos::new(0, row as u16))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn nav(list: &ListView<Vec<&str>>, nav: NavKey) -> Option<Action> {
+        list.key_event(KeyPress::Navigation(nav), ModKeys::Empty)
+    }
+
+    #[test]
+    fn navigation() {
+        let list = ListView::new(vec!["a", "b", "c", "d", "e", "f"]);
+        assert_eq!(list.visible(Pos::default(), 2), 0..2);
+        assert_eq!(nav(&list, NavKey::Down), None);
+        list.focus(FocusEvent::Offer);
+        assert_eq!(nav(&list, NavKey::Down), Some(Action::Selected(1)));
+        assert_eq!(nav(&list, NavKey::PageDown), Some(Action::Selected(3)));
+        assert_eq!(list.visible(Pos::default(), 2), 2..4);
+        assert_eq!(nav(&list, NavKey::End), Some(Action::Selected(5)));
+        assert_eq!(nav(&list, NavKey::Down), Some(Action::Redraw()));
+        assert_eq!(nav(&list, NavKey::Home), Some(Action::Selected(0)));
+        assert_eq!(list.visible(Pos::default(), 2), 0..2);
+        assert_eq!(list.visible(Pos::new(0, 3), 2), 3..5);
+    }
+
+    #[test]
+    fn multiple() {
+        let list = ListView::new(vec!["a", "b", "c", "d"])
+            .with_selection_mode(SelectionMode::Multiple);
+        list.visible(Pos::default(), 4);
+        list.focus(FocusEvent::Offer);
+        let space = KeyPress::Character(' ');
+        let down = KeyPress::Navigation(NavKey::Down);
+        assert_eq!(
+            list.key_event(space, ModKeys::Empty),
+            Some(Action::SelectionChanged(vec![0]))
+        );
+        assert_eq!(
+            list.key_event(down, ModKeys::Empty),
+            Some(Action::Redraw())
+        );
+        assert_eq!(
+            list.key_event(down, ModKeys::Shift),
+            Some(Action::SelectionChanged(vec![0, 1, 2]))
+        );
+        let click = MouseEvent::ButtonDown(MouseButton::Left);
+        let dim = Dim::new(4, 4);
+        assert_eq!(
+            list.mouse_event(click, ModKeys::Control, dim, Pos::new(0, 1)),
+            Some(Action::SelectionChanged(vec![0, 2]))
+        );
+    }
+
+    #[test]
+    fn type_ahead() {
+        let list =
+            ListView::new(vec!["apple", "banana", "blueberry", "cherry"]);
+        list.focus(FocusEvent::Offer);
+        let key = |ch| list.key_event(KeyPress::Character(ch), ModKeys::Empty);
+        assert_eq!(key('b'), Some(Action::Selected(1)));
+        assert_eq!(key('l'), Some(Action::Selected(2)));
+        assert_eq!(key('x'), None);
+        list.search_time.set(None);
+        assert_eq!(key('C'), Some(Action::Selected(3)));
+    }
+
+    #[test]
+    fn space_after_search() {
+        let list = ListView::new(vec!["a b", "a c", "b"])
+            .with_selection_mode(SelectionMode::Multiple);
+        list.focus(FocusEvent::Offer);
+        let key = |ch| list.key_event(KeyPress::Character(ch), ModKeys::Empty);
+        assert_eq!(key('a'), Some(Action::Redraw()));
+        // Space continues a recent search
+        assert_eq!(key(' '), Some(Action::Redraw()));
+        assert!(list.selected_all().is_empty());
+        let expired = Instant::now().checked_sub(TYPE_AHEAD_TIMEOUT);
+        list.search_time.set(expired);
+        assert_eq!(key(' '), Some(Action::SelectionChanged(vec![1])));
+    }
+}
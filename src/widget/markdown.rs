@@ -0,0 +1,822 @@
+// markdown.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{Action, ModKeys, MouseButton, MouseEvent};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{
+    line_width, push_span, reorder_spans, sanitize, wrap_spans, Align,
+    Appearance, ControlChars, Direction, Outline, Span, StyleGroup, Theme,
+    Weight,
+};
+use crate::{Result, Widget};
+use pulldown_cmark::{Alignment, Event, Options, Parser, Tag};
+use std::cell::{Ref, RefCell};
+use std::iter::Peekable;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Peekable markdown event iterator
+type Events<'a> = Peekable<Parser<'a>>;
+
+/// Line of styled text, with paragraph direction
+type Line = (Direction, Vec<Span>);
+
+/// Lines of styled text
+type Lines = Vec<Line>;
+
+/// Markdown block element
+#[derive(Clone, Debug, PartialEq)]
+enum Block {
+    /// Paragraph of text
+    Paragraph(Vec<Span>),
+    /// Heading (level 1 to 6)
+    Heading(u32, Vec<Span>),
+    /// Code block lines
+    Code(Vec<String>),
+    /// List items, with start number for ordered lists
+    List(Option<u64>, Vec<Vec<Block>>),
+    /// Block quote
+    Quote(Vec<Block>),
+    /// Table alignments, header cells and rows
+    Table(Vec<Alignment>, Vec<Vec<Span>>, Vec<Vec<Vec<Span>>>),
+    /// Thematic break
+    Rule,
+}
+
+/// Inline text style
+#[derive(Clone, Copy, Debug, Default)]
+struct Inline<'a> {
+    /// Style group
+    group: Option<StyleGroup>,
+    /// Appearance override
+    appearance: Option<Appearance>,
+    /// Hyperlink target
+    link: Option<&'a str>,
+}
+
+/// Area of a drawn hyperlink (row, columns and target)
+type LinkArea = (u16, Range<u16>, String);
+
+/// Layout cache key (width, tab stop and control character replacement)
+type LayoutKey = (u16, u16, ControlChars);
+
+/// Markdown text widget
+///
+/// Renders [CommonMark] text, plus tables, strikethrough, task lists and
+/// footnotes.  Text is wrapped to the widget width, so it can be wrapped with
+/// a [ScrollView] for long documents.
+///
+/// Element         | Style
+/// ----------------|------------------------------------------
+/// Heading         | [StyleGroup::Primary] with **bold** weight
+/// Emphasis        | _Italic_
+/// Strong          | **Bold**
+/// Strikethrough   | ~~Strikethrough~~
+/// Code            | `Reverse`
+/// Link            | [StyleGroup::Hovered] with <u>underline</u> (hyperlink)
+/// Quote, list, table and rule decorations | [StyleGroup::Primary]
+///
+/// Raw HTML blocks are not displayed.
+///
+/// ```rust
+/// use semtext::widget::Markdown;
+///
+/// let help = Markdown::new("# Help\n\nPress *Esc* to quit.")
+///     .into_scroll_view();
+/// # use semtext::Widget;
+/// ```
+///
+/// [CommonMark]: https://commonmark.org
+/// [ScrollView]: struct.ScrollView.html
+/// [StyleGroup::Primary]: ../text/enum.StyleGroup.html#variant.Primary
+/// [StyleGroup::Hovered]: ../text/enum.StyleGroup.html#variant.Hovered
+pub struct Markdown {
+    /// Parsed block elements
+    blocks: Vec<Block>,
+    /// Cached layout lines
+    layout: RefCell<(Option<LayoutKey>, Lines)>,
+    /// Hyperlink areas from last draw
+    links: RefCell<Vec<LinkArea>>,
+}
+
+impl<'a> Inline<'a> {
+    /// Create a span with the inline style
+    fn span(self, text: &str) -> Span {
+        let mut span = Span::new(text);
+        if let Some(group) = self.group {
+            span = span.with_group(group);
+        }
+        if let Some(app) = self.appearance {
+            span = span.with_appearance(app);
+        }
+        if let Some(link) = self.link {
+            span = span.with_link(link);
+        }
+        span
+    }
+
+    /// Set the style group
+    fn with_group(mut self, group: StyleGroup) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Set the hyperlink target
+    fn with_link(mut self, link: &'a str) -> Self {
+        self.link = Some(link);
+        self
+    }
+
+    /// Adjust the appearance
+    fn with<F>(mut self, adjust: F) -> Self
+    where
+        F: FnOnce(Appearance) -> Appearance,
+    {
+        self.appearance = Some(adjust(self.appearance.unwrap_or_default()));
+        self
+    }
+}
+
+/// Check if a tag is a block-level element
+fn is_block(tag: &Tag) -> bool {
+    !matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Link(..)
+            | Tag::Image(..)
+    )
+}
+
+/// Parse block elements until the end of a container
+fn parse_blocks(events: &mut Events) -> Vec<Block> {
+    let mut blocks = vec![];
+    while let Some(ev) = events.peek() {
+        match ev {
+            Event::Start(tag) if is_block(tag) => {
+                if let Some(Event::Start(tag)) = events.next() {
+                    parse_block(events, tag, &mut blocks);
+                }
+            }
+            Event::End(_) => {
+                events.next();
+                break;
+            }
+            Event::Rule => {
+                events.next();
+                blocks.push(Block::Rule);
+            }
+            Event::Html(_) => {
+                // Raw HTML blocks are not displayed
+                events.next();
+            }
+            _ => {
+                // Inline elements in a "tight" list item
+                let mut spans = vec![];
+                parse_inline_run(events, &mut spans);
+                blocks.push(Block::Paragraph(spans));
+            }
+        }
+    }
+    blocks
+}
+
+/// Parse one block element, after its start tag
+fn parse_block(events: &mut Events, tag: Tag, blocks: &mut Vec<Block>) {
+    match tag {
+        Tag::Paragraph => {
+            let mut spans = vec![];
+            parse_inline(events, Inline::default(), &mut spans);
+            blocks.push(Block::Paragraph(spans));
+        }
+        Tag::Heading(level) => {
+            let style =
+                Inline::default().with_group(StyleGroup::Primary).with(|a| {
+                    a.with_weight(Weight::Bold).with_underline(level == 1)
+                });
+            let mut spans = vec![];
+            parse_inline(events, style, &mut spans);
+            blocks.push(Block::Heading(level, spans));
+        }
+        Tag::CodeBlock(_) => {
+            let mut code = String::new();
+            for ev in events {
+                match ev {
+                    Event::Text(text) => code.push_str(&text),
+                    _ => break,
+                }
+            }
+            let lines = code.lines().map(String::from).collect();
+            blocks.push(Block::Code(lines));
+        }
+        Tag::List(start) => {
+            let mut items = vec![];
+            while let Some(ev) = events.next() {
+                match ev {
+                    Event::Start(Tag::Item) => items.push(parse_blocks(events)),
+                    _ => break,
+                }
+            }
+            blocks.push(Block::List(start, items));
+        }
+        Tag::BlockQuote => blocks.push(Block::Quote(parse_blocks(events))),
+        Tag::FootnoteDefinition(label) => {
+            let style = Inline::default().with_group(StyleGroup::Primary);
+            let label = style.span(&format!("[^{}]:", label));
+            blocks.push(Block::Paragraph(vec![label]));
+            blocks.extend(parse_blocks(events));
+        }
+        Tag::Table(aligns) => {
+            let mut head = vec![];
+            let mut rows = vec![];
+            while let Some(ev) = events.next() {
+                match ev {
+                    Event::Start(Tag::TableHead) => head = parse_row(events),
+                    Event::Start(Tag::TableRow) => rows.push(parse_row(events)),
+                    Event::End(Tag::Table(_)) => break,
+                    _ => (),
+                }
+            }
+            blocks.push(Block::Table(aligns, head, rows));
+        }
+        _ => blocks.extend(parse_blocks(events)),
+    }
+}
+
+/// Parse cells of a table row
+fn parse_row(events: &mut Events) -> Vec<Vec<Span>> {
+    let mut cells = vec![];
+    while let Some(ev) = events.next() {
+        match ev {
+            Event::Start(Tag::TableCell) => {
+                let mut spans = vec![];
+                parse_inline(events, Inline::default(), &mut spans);
+                cells.push(spans);
+            }
+            Event::Start(Tag::TableRow) => (),
+            _ => break,
+        }
+    }
+    cells
+}
+
+/// Parse inline elements until the end of a container
+fn parse_inline(events: &mut Events, style: Inline, spans: &mut Vec<Span>) {
+    while let Some(ev) = events.next() {
+        if let Event::End(_) = ev {
+            break;
+        }
+        inline_event(events, ev, style, spans);
+    }
+}
+
+/// Parse inline elements until the start or end of a block element
+fn parse_inline_run(events: &mut Events, spans: &mut Vec<Span>) {
+    while let Some(ev) = events.peek() {
+        match ev {
+            Event::Start(tag) | Event::End(tag) if is_block(tag) => break,
+            Event::Rule | Event::Html(_) => break,
+            _ => {
+                if let Some(ev) = events.next() {
+                    inline_event(events, ev, Inline::default(), spans);
+                }
+            }
+        }
+    }
+}
+
+/// Handle an inline event
+fn inline_event(
+    events: &mut Events,
+    ev: Event,
+    style: Inline,
+    spans: &mut Vec<Span>,
+) {
+    match ev {
+        Event::Text(text) | Event::Html(text) => {
+            push_span(spans, style.span(&text))
+        }
+        Event::Code(text) => {
+            push_span(spans, style.with(|a| a.with_reverse(true)).span(&text))
+        }
+        Event::SoftBreak => push_span(spans, style.span(" ")),
+        Event::HardBreak => push_span(spans, style.span("\n")),
+        Event::FootnoteReference(label) => {
+            push_span(spans, style.span(&format!("[^{}]", label)))
+        }
+        Event::TaskListMarker(checked) => {
+            let marker = if checked { "[x] " } else { "[ ] " };
+            push_span(spans, style.span(marker));
+        }
+        Event::Start(Tag::Emphasis) => {
+            parse_inline(events, style.with(|a| a.with_italic(true)), spans)
+        }
+        Event::Start(Tag::Strong) => {
+            let style = style.with(|a| a.with_weight(Weight::Bold));
+            parse_inline(events, style, spans);
+        }
+        Event::Start(Tag::Strikethrough) => {
+            let style = style.with(|a| a.with_strikethrough(true));
+            parse_inline(events, style, spans);
+        }
+        Event::Start(Tag::Link(_, dest, _)) => {
+            let style = style
+                .with_group(StyleGroup::Hovered)
+                .with(|a| a.with_underline(true))
+                .with_link(&dest);
+            parse_inline(events, style, spans);
+        }
+        Event::Start(Tag::Image(..)) => {
+            push_span(spans, style.span("["));
+            parse_inline(events, style, spans);
+            push_span(spans, style.span("]"));
+        }
+        Event::Start(_) => parse_inline(events, style, spans),
+        _ => (),
+    }
+}
+
+/// Decoration span
+fn decoration(text: &str) -> Span {
+    Span::new(text).with_group(StyleGroup::Primary)
+}
+
+/// Add lines with a prefix
+///
+/// * `lines`: Lines to add
+/// * `first`: Prefix for first line
+/// * `rest`: Prefix for remaining lines
+/// * `out`: Output lines
+fn prefix_lines(lines: Lines, first: &Span, rest: &Span, out: &mut Lines) {
+    for (i, (dir, line)) in lines.into_iter().enumerate() {
+        let prefix = if i == 0 { first } else { rest };
+        let mut pline = vec![prefix.clone()];
+        pline.extend(line);
+        out.push((dir, pline));
+    }
+}
+
+/// Detect the paragraph direction of spans
+fn detect_direction(spans: &[Span]) -> Direction {
+    let text: String = spans.iter().map(Span::text).collect();
+    Direction::detect(&text)
+}
+
+/// Add wrapped lines of a paragraph
+fn push_paragraph(lines: Vec<Vec<Span>>, dir: Direction, out: &mut Lines) {
+    out.extend(lines.into_iter().map(|line| (dir, line)));
+}
+
+/// Lay out block elements
+///
+/// * `blocks`: Block elements
+/// * `theme`: Style theme
+/// * `width`: Width in text cells
+/// * `spaced`: Add blank lines between blocks
+/// * `out`: Output lines
+fn layout_blocks(
+    blocks: &[Block],
+    theme: &Theme,
+    width: usize,
+    spaced: bool,
+    out: &mut Lines,
+) {
+    for (i, block) in blocks.iter().enumerate() {
+        if spaced && i > 0 {
+            out.push((Direction::LeftToRight, vec![]));
+        }
+        block.layout(theme, width, out);
+    }
+}
+
+/// Get the natural (unwrapped) width of block elements
+fn natural_width(blocks: &[Block], theme: &Theme) -> usize {
+    blocks
+        .iter()
+        .map(|block| block.natural_width(theme))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Pad a line of spans to a width, using an alignment
+fn align_line(
+    mut line: Vec<Span>,
+    width: usize,
+    align: Alignment,
+) -> Vec<Span> {
+    let pad = width.saturating_sub(line_width(&line));
+    let (left, right) = match align {
+        Alignment::Right => (pad, 0),
+        Alignment::Center => (pad / 2, pad - pad / 2),
+        _ => (0, pad),
+    };
+    if left > 0 {
+        line.insert(0, Span::new(&" ".repeat(left)));
+    }
+    if right > 0 {
+        line.push(Span::new(&" ".repeat(right)));
+    }
+    line
+}
+
+/// Calculate table column widths
+///
+/// * `natural`: Natural (unwrapped) widths of all columns
+/// * `avail`: Available width for all columns
+fn column_widths(mut widths: Vec<usize>, avail: usize) -> Vec<usize> {
+    while widths.iter().sum::<usize>() > avail {
+        match widths.iter_mut().max() {
+            Some(w) if *w > 1 => *w -= 1,
+            _ => break,
+        }
+    }
+    widths
+}
+
+impl Block {
+    /// Get the natural (unwrapped) width of a block element
+    ///
+    /// Rules fill the available width, so they have no natural width.
+    fn natural_width(&self, theme: &Theme) -> usize {
+        match self {
+            Block::Rule => 0,
+            Block::Quote(blocks) => 2 + natural_width(blocks, theme),
+            Block::List(start, items) => {
+                let items = items.iter().map(|item| natural_width(item, theme));
+                marker_width(*start, items.len()) + items.max().unwrap_or(0)
+            }
+            _ => {
+                let mut lines = vec![];
+                self.layout(theme, usize::MAX, &mut lines);
+                lines
+                    .iter()
+                    .map(|(_, line)| line_width(line))
+                    .max()
+                    .unwrap_or(0)
+            }
+        }
+    }
+
+    /// Lay out a block element into lines
+    fn layout(&self, theme: &Theme, width: usize, out: &mut Lines) {
+        match self {
+            Block::Paragraph(spans) | Block::Heading(_, spans) => {
+                let dir = detect_direction(spans);
+                push_paragraph(wrap_spans(spans, width), dir, out);
+            }
+            Block::Code(code) => layout_code(code, theme, out),
+            Block::List(start, items) => {
+                layout_list(*start, items, theme, width, out)
+            }
+            Block::Quote(blocks) => {
+                let mut lines = vec![];
+                let inner = width.saturating_sub(2);
+                layout_blocks(blocks, theme, inner, true, &mut lines);
+                let bar =
+                    decoration(&format!("{} ", Outline::default().left()));
+                prefix_lines(lines, &bar, &bar, out);
+            }
+            Block::Table(aligns, head, rows) => {
+                layout_table(aligns, head, rows, width, out)
+            }
+            Block::Rule => {
+                let rule: String = std::iter::repeat(Outline::default().top())
+                    .take(width)
+                    .collect();
+                out.push((Direction::LeftToRight, vec![decoration(&rule)]));
+            }
+        }
+    }
+}
+
+/// Lay out a code block
+fn layout_code(code: &[String], theme: &Theme, out: &mut Lines) {
+    let style = Inline::default().with(|a| a.with_reverse(true));
+    let lines: Vec<_> = code
+        .iter()
+        .map(|line| sanitize(line, 0, theme.tab_stop, theme.control_chars))
+        .collect();
+    let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+    let dir = Direction::detect(&lines.join("\n"));
+    for line in lines {
+        let pad = width - line.width();
+        let text = format!(" {}{} ", line, " ".repeat(pad));
+        out.push((dir, vec![Span::new(" "), style.span(&text)]));
+    }
+}
+
+/// Get the width of list item markers
+fn marker_width(start: Option<u64>, len: usize) -> usize {
+    let last = start.map(|s| s + len.saturating_sub(1) as u64);
+    last.map_or(2, |l| l.to_string().len() + 2)
+}
+
+/// Lay out a list
+fn layout_list(
+    start: Option<u64>,
+    items: &[Vec<Block>],
+    theme: &Theme,
+    width: usize,
+    out: &mut Lines,
+) {
+    let marker_width = marker_width(start, items.len());
+    let inner = width.saturating_sub(marker_width);
+    let rest = Span::new(&" ".repeat(marker_width));
+    for (i, item) in items.iter().enumerate() {
+        let marker = match start {
+            Some(s) => {
+                let num = s + i as u64;
+                format!("{:>w$}. ", num, w = marker_width - 2)
+            }
+            None => "• ".to_string(),
+        };
+        let mut lines = vec![];
+        layout_blocks(item, theme, inner, false, &mut lines);
+        prefix_lines(lines, &decoration(&marker), &rest, out);
+    }
+}
+
+/// Lay out a table
+fn layout_table(
+    aligns: &[Alignment],
+    head: &[Vec<Span>],
+    rows: &[Vec<Vec<Span>>],
+    width: usize,
+    out: &mut Lines,
+) {
+    let cols = aligns.len();
+    if cols == 0 {
+        return;
+    }
+    let mut natural = vec![1; cols];
+    for row in std::iter::once(head).chain(rows.iter().map(|r| &r[..])) {
+        for (w, cell) in natural.iter_mut().zip(row) {
+            *w = (*w).max(line_width(cell));
+        }
+    }
+    let avail = width.saturating_sub(3 * (cols - 1));
+    let widths = column_widths(natural, avail);
+    let dir = detect_direction(&head.concat());
+    let bold = Inline::default().with(|a| a.with_weight(Weight::Bold));
+    let head: Vec<Vec<Span>> = head
+        .iter()
+        .map(|cell| {
+            let text: String = cell.iter().map(Span::text).collect();
+            vec![bold.span(&text)]
+        })
+        .collect();
+    layout_row(aligns, &head, &widths, dir, out);
+    let sep = Outline::default().top();
+    let rule: Vec<String> = widths
+        .iter()
+        .map(|w| std::iter::repeat(sep).take(*w).collect())
+        .collect();
+    let rule = rule.join(&format!("{}┼{}", sep, sep));
+    out.push((dir, vec![decoration(&rule)]));
+    for row in rows {
+        layout_row(aligns, row, &widths, dir, out);
+    }
+}
+
+/// Lay out a table row
+fn layout_row(
+    aligns: &[Alignment],
+    row: &[Vec<Span>],
+    widths: &[usize],
+    dir: Direction,
+    out: &mut Lines,
+) {
+    let empty = vec![];
+    let cells: Vec<Vec<Vec<Span>>> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, w)| wrap_spans(row.get(i).unwrap_or(&empty), *w))
+        .collect();
+    let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let sep = decoration(&format!(" {} ", Outline::default().left()));
+    for r in 0..height {
+        let mut line = vec![];
+        for (c, cell) in cells.iter().enumerate() {
+            if c > 0 {
+                line.push(sep.clone());
+            }
+            let text = cell.get(r).cloned().unwrap_or_default();
+            line.extend(align_line(text, widths[c], aligns[c]));
+        }
+        out.push((dir, line));
+    }
+}
+
+impl Markdown {
+    /// Create a new markdown widget
+    pub fn new(text: &str) -> Self {
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS;
+        let mut events = Parser::new_ext(text, options).peekable();
+        let blocks = parse_blocks(&mut events);
+        let layout = RefCell::new((None, vec![]));
+        let links = RefCell::new(vec![]);
+        Markdown {
+            blocks,
+            layout,
+            links,
+        }
+    }
+
+    /// Lay out into lines of styled text
+    ///
+    /// The lines are cached until the width or theme tab settings change.
+    fn layout(&self, theme: &Theme, width: u16) -> Ref<'_, Lines> {
+        let key = (width, theme.tab_stop, theme.control_chars);
+        let cached = self.layout.borrow().0 == Some(key);
+        if !cached {
+            let mut lines = vec![];
+            layout_blocks(
+                &self.blocks,
+                theme,
+                usize::from(width),
+                true,
+                &mut lines,
+            );
+            self.layout.replace((Some(key), lines));
+        }
+        Ref::map(self.layout.borrow(), |(_, lines)| lines)
+    }
+}
+
+impl Widget for Markdown {
+    /// Get the width bounds
+    fn width_bounds(&self, theme: &Theme) -> LengthBound {
+        let w = natural_width(&self.blocks, theme).min(20) as u16;
+        LengthBound::new(w..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, theme: &Theme, width: u16) -> LengthBound {
+        let rows = self.layout(theme, width).len() as u16;
+        LengthBound::new(rows..=rows)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let top = usize::from(offset.row);
+        let height = usize::from(cells.height());
+        let lines = self.layout(cells.theme(), cells.width());
+        let mut links = self.links.borrow_mut();
+        links.clear();
+        for (row, (dir, line)) in
+            lines.iter().skip(top).take(height).enumerate()
+        {
+            let row = row as u16; // limited to u16 by take(height)
+            let dir = *dir;
+            let line = reorder_spans(line, dir);
+            let width = line_width(&line) as u16;
+            let mut col = Align::Start.offset(dir, cells.width(), width);
+            cells.move_to(col, row)?;
+            for span in &line {
+                cells.print_span(span)?;
+                let end = col.saturating_add(span.width() as u16);
+                if let Some(link) = span.link() {
+                    links.push((offset.row + row, col..end, link.to_string()));
+                }
+                col = end;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle mouse events
+    ///
+    /// Clicking a link produces an `Action::Link`.
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        match mev {
+            MouseEvent::ButtonUp(MouseButton::Left) => self
+                .links
+                .borrow()
+                .iter()
+                .find(|(row, cols, _)| {
+                    *row == pos.row && cols.contains(&pos.col)
+                })
+                .map(|(_, _, link)| Action::Link(link.clone())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn texts(md: &str, width: u16) -> Vec<String> {
+        Markdown::new(md)
+            .layout(&Theme::default(), width)
+            .iter()
+            .map(|(_, l)| l.iter().map(Span::text).collect())
+            .collect()
+    }
+
+    #[test]
+    fn paragraphs() {
+        let md = "# Title\n\nSome *emphasized* text\nand more.";
+        assert_eq!(
+            texts(md, 12),
+            ["Title", "", "Some ", "emphasized ", "text and ", "more."]
+        );
+    }
+
+    #[test]
+    fn direction() {
+        let md = Markdown::new("שלום 123 456\n\nabc");
+        let dirs: Vec<Direction> = md
+            .layout(&Theme::default(), 5)
+            .iter()
+            .map(|(dir, _)| *dir)
+            .collect();
+        assert_eq!(
+            dirs,
+            [
+                Direction::RightToLeft,
+                Direction::RightToLeft,
+                Direction::RightToLeft,
+                Direction::LeftToRight,
+                Direction::LeftToRight,
+            ]
+        );
+    }
+
+    #[test]
+    fn lists() {
+        let md = "* one\n* two\n  * nested\n\n9. nine\n10. ten";
+        assert_eq!(
+            texts(md, 20),
+            ["• one", "• two", "  • nested", "", " 9. nine", "10. ten"]
+        );
+    }
+
+    #[test]
+    fn quote_code() {
+        let md = "> quoted\n\n```\nfn x()\n{}\n```";
+        assert_eq!(texts(md, 20), ["│ quoted", "", "  fn x() ", "  {}     "]);
+    }
+
+    #[test]
+    fn html() {
+        let md = "above\n\n<div>\nhidden\n</div>\n\nbelow";
+        assert_eq!(texts(md, 20), ["above", "", "below"]);
+    }
+
+    #[test]
+    fn table() {
+        let md = "| a | b |\n|---|--:|\n| 1 | 22 |";
+        assert_eq!(texts(md, 20), ["a │  b", "──┼───", "1 │ 22"]);
+    }
+
+    #[test]
+    fn bounds() {
+        let md = Markdown::new("Some text which wraps");
+        let theme = Theme::default();
+        assert_eq!(md.width_bounds(&theme), LengthBound::new(20..));
+        assert_eq!(md.height_bounds(&theme, 10), LengthBound::new(3..=3));
+        let md = Markdown::new("---");
+        assert_eq!(md.width_bounds(&theme), LengthBound::new(0..));
+        let md = Markdown::new("> # Title\n\n---");
+        assert_eq!(md.width_bounds(&theme), LengthBound::new(7..));
+    }
+
+    #[test]
+    fn cache() {
+        let md = Markdown::new("Some text which wraps\n\n---");
+        let theme = Theme::default();
+        assert_eq!(md.height_bounds(&theme, 10), LengthBound::new(5..=5));
+        assert_eq!(md.layout.borrow().0, Some((10, 8, ControlChars::Caret)));
+        assert_eq!(md.height_bounds(&theme, 30), LengthBound::new(3..=3));
+        assert_eq!(md.layout.borrow().0, Some((30, 8, ControlChars::Caret)));
+        assert_eq!(line_width(&md.layout(&theme, 30)[2].1), 30);
+    }
+
+    #[test]
+    fn links() {
+        let md = Markdown::new("See [docs](https://docs.rs) now");
+        let lines = md.layout(&Theme::default(), 20);
+        let links: Vec<(&str, Option<&str>)> =
+            lines[0].1.iter().map(|s| (s.text(), s.link())).collect();
+        assert_eq!(
+            links,
+            [
+                ("See ", None),
+                ("docs", Some("https://docs.rs")),
+                (" now", None)
+            ]
+        );
+    }
+}
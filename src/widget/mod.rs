@@ -6,12 +6,42 @@
 
 mod border;
 mod button;
+mod checkbox;
+mod codeview;
+mod edit;
 mod label;
+mod listview;
+mod markdown;
+mod radio;
 mod scrollview;
 mod spacer;
+mod styled;
+mod switch;
+mod table;
+mod tabs;
+mod textarea;
+mod textinput;
+mod treeview;
+mod virtuallist;
 
 pub use border::{Border, BorderStyle};
 pub use button::Button;
+pub(crate) use button::ButtonState;
+pub use checkbox::{CheckBox, CheckState};
+pub use codeview::CodeView;
+pub(crate) use edit::{visible_graphemes, EditBuffer};
 pub use label::Label;
+pub(crate) use listview::{fill_row, list_style_group, nav_index, RowScroll};
+pub use listview::{ListSource, ListView, SelectionMode};
+pub use markdown::Markdown;
+pub use radio::{Orientation, RadioGroup};
 pub use scrollview::{ScrollBar, ScrollView};
 pub use spacer::Spacer;
+pub use styled::Styled;
+pub use switch::Switch;
+pub use table::{Column, SortOrder, Table, TableSource};
+pub use tabs::Tabs;
+pub use textarea::TextArea;
+pub use textinput::TextInput;
+pub use treeview::{TreeSource, TreeView};
+pub use virtuallist::VirtualList;
@@ -0,0 +1,282 @@
+// radio.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent, NavKey};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{StyleGroup, Theme};
+use crate::widget::{list_style_group, ButtonState};
+use crate::{Result, Widget};
+use std::cell::Cell;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns between options laid out horizontally
+const GAP: u16 = 2;
+
+/// Layout orientation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Orientation {
+    /// Top to bottom
+    Vertical,
+    /// Left to right
+    Horizontal,
+}
+
+/// Radio button group widget
+///
+/// One of a set of mutually exclusive options is selected.  While focused,
+/// arrow keys (and `Home` / `End`) change the selection; options can also be
+/// clicked.  Changing the selection produces an `Action::Selected` with its
+/// index.
+pub struct RadioGroup {
+    /// Option labels
+    options: Vec<String>,
+    /// Layout orientation
+    orientation: Orientation,
+    /// Button state
+    button: ButtonState,
+    /// Selected option index
+    selected: Cell<usize>,
+}
+
+impl RadioGroup {
+    /// Create a new radio button group, with the first option selected
+    pub fn new(options: &[&str]) -> Self {
+        let options = options.iter().map(|o| o.to_string()).collect();
+        let orientation = Orientation::Vertical;
+        let button = ButtonState::new();
+        let selected = Cell::new(0);
+        Self {
+            options,
+            orientation,
+            button,
+            selected,
+        }
+    }
+
+    /// Set the layout orientation (vertical by default)
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Set the initially selected option
+    pub fn with_selected(self, index: usize) -> Self {
+        self.set_selected(index);
+        self
+    }
+
+    /// Get the selected option index
+    pub fn selected(&self) -> usize {
+        self.selected.get()
+    }
+
+    /// Set the selected option index
+    ///
+    /// Out of range indices are ignored.
+    pub fn set_selected(&self, index: usize) {
+        if index < self.options.len() {
+            self.selected.set(index);
+        }
+    }
+
+    /// Disable the radio group
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the radio group
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Get the width of an option, including indicator
+    fn option_width(option: &str) -> u16 {
+        (option.width() as u16).saturating_add(4)
+    }
+
+    /// Get the columns of each option, when laid out horizontally
+    fn option_cols(&self) -> impl Iterator<Item = Range<u16>> + '_ {
+        self.options.iter().scan(0, |col: &mut u16, option| {
+            let start = *col;
+            let end = start.saturating_add(Self::option_width(option));
+            *col = end.saturating_add(GAP);
+            Some(start..end)
+        })
+    }
+
+    /// Get the option at a position
+    fn option_at(&self, pos: Pos) -> Option<usize> {
+        match self.orientation {
+            Orientation::Vertical => Some(usize::from(pos.row))
+                .filter(|row| *row < self.options.len()),
+            Orientation::Horizontal => self
+                .option_cols()
+                .position(|cols| cols.contains(&pos.col))
+                .filter(|_| pos.row == 0),
+        }
+    }
+
+    /// Select an option, producing an action if changed
+    fn select(&self, index: usize) -> Option<Action> {
+        if index != self.selected.get() && index < self.options.len() {
+            self.selected.set(index);
+            Some(Action::Selected(index))
+        } else {
+            None
+        }
+    }
+}
+
+impl Widget for RadioGroup {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        list_style_group(&self.button)
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        let w = match self.orientation {
+            Orientation::Vertical => self
+                .options
+                .iter()
+                .map(|o| Self::option_width(o))
+                .max()
+                .unwrap_or(0),
+            Orientation::Horizontal => {
+                self.option_cols().last().map_or(0, |cols| cols.end)
+            }
+        };
+        LengthBound::new(w..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        let h = match self.orientation {
+            Orientation::Vertical => self.options.len() as u16,
+            Orientation::Horizontal => 1,
+        };
+        LengthBound::new(h..=h)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let style = cells.style(self.style_group());
+        let selected_style = cells.style(self.button.style_group());
+        let cols: Vec<_> = self.option_cols().collect();
+        for (i, option) in self.options.iter().enumerate() {
+            let pos = match self.orientation {
+                Orientation::Vertical => Pos::new(0, i as u16),
+                Orientation::Horizontal => Pos::new(cols[i].start, 0),
+            };
+            if pos.row < offset.row {
+                continue;
+            }
+            // Options scrolled off the left are cut by the offset
+            let cut = offset.col.saturating_sub(pos.col);
+            let pos = Pos::new(pos.col.max(offset.col), pos.row) - offset;
+            cells.move_to(pos.col, pos.row)?;
+            if i == self.selected.get() {
+                cells.set_style(selected_style)?;
+                cells.print_str_cut(&format!("(•) {}", option), cut)?;
+            } else {
+                cells.set_style(style)?;
+                cells.print_str_cut(&format!("( ) {}", option), cut)?;
+            }
+        }
+        cells.set_style(style)
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        let redraw = self.button.mouse_event(mev);
+        match mev {
+            MouseEvent::ButtonDown(_) if self.button.is_focused() => {
+                self.option_at(pos).and_then(|i| self.select(i)).or(redraw)
+            }
+            _ => redraw,
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if !self.button.is_focused() || mods != ModKeys::Empty {
+            return None;
+        }
+        let selected = self.selected.get();
+        let last = self.options.len().saturating_sub(1);
+        let (prev, next) = match self.orientation {
+            Orientation::Vertical => (NavKey::Up, NavKey::Down),
+            Orientation::Horizontal => (NavKey::Left, NavKey::Right),
+        };
+        match key {
+            KeyPress::Navigation(nav) if nav == prev => {
+                self.select(selected.saturating_sub(1))
+            }
+            KeyPress::Navigation(nav) if nav == next => {
+                self.select((selected + 1).min(last))
+            }
+            KeyPress::Navigation(NavKey::Home) => self.select(0),
+            KeyPress::Navigation(NavKey::End) => self.select(last),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::MouseButton;
+
+    #[test]
+    fn keys() {
+        let radio = RadioGroup::new(&["a", "b", "c"]);
+        let down = KeyPress::Navigation(NavKey::Down);
+        assert_eq!(radio.key_event(down, ModKeys::Empty), None);
+        radio.focus(FocusEvent::Offer);
+        assert_eq!(
+            radio.key_event(down, ModKeys::Empty),
+            Some(Action::Selected(1))
+        );
+        let end = KeyPress::Navigation(NavKey::End);
+        assert_eq!(
+            radio.key_event(end, ModKeys::Empty),
+            Some(Action::Selected(2))
+        );
+        assert_eq!(radio.key_event(down, ModKeys::Empty), None);
+        let right = KeyPress::Navigation(NavKey::Right);
+        assert_eq!(radio.key_event(right, ModKeys::Empty), None);
+    }
+
+    #[test]
+    fn mouse() {
+        let radio = RadioGroup::new(&["one", "two", "three"])
+            .with_orientation(Orientation::Horizontal);
+        let cols: Vec<_> = radio.option_cols().collect();
+        assert_eq!(cols, [0..7, 9..16, 18..27]);
+        let down = MouseEvent::ButtonDown(MouseButton::Left);
+        let dim = Dim::new(27, 1);
+        radio.focus(FocusEvent::Offer);
+        assert_eq!(
+            radio.mouse_event(down, ModKeys::Empty, dim, Pos::new(20, 0)),
+            Some(Action::Selected(2))
+        );
+        assert_eq!(
+            radio.mouse_event(down, ModKeys::Empty, dim, Pos::new(8, 0)),
+            None
+        );
+        assert_eq!(radio.selected(), 2);
+    }
+}
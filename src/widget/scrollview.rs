@@ -2,7 +2,7 @@
 //
 // Copyright (c) 2020-2021  Douglas P Lau
 //
-use crate::input::{Action, FocusEvent, ModKeys, MouseEvent};
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent};
 use crate::layout::{BBox, Cells, Dim, LengthBound, Pos};
 use crate::text::{StyleGroup, Theme};
 use crate::{Result, Widget};
@@ -64,6 +64,8 @@ pub struct ScrollView<W: Widget> {
     offset: Cell<Pos>,
     /// Widget state
     state: Cell<State>,
+    /// Cursor position of wrapped widget (from last draw)
+    cursor: Cell<Option<Pos>>,
 }
 
 impl VerticalScrollBar {
@@ -188,7 +190,6 @@ impl Widget for HorizontalScrollBar {
 
     /// Draw the widget
     fn draw(&self, cells: &mut Cells, pos: Pos) -> Result<()> {
-        assert_eq!(pos, Pos::default(), "FIXME");
         let width = self.width.get();
         let bar_width = cells.width();
         if bar_width <= width {
@@ -213,12 +214,14 @@ impl<W: Widget> ScrollView<W> {
         let h_bar = None;
         let offset = Cell::new(Pos::default());
         let state = Cell::new(State::Enabled);
+        let cursor = Cell::new(None);
         Self {
             wrapped,
             v_bar,
             h_bar,
             offset,
             state,
+            cursor,
         }
     }
 
@@ -268,6 +271,32 @@ impl<W: Widget> ScrollView<W> {
         action
     }
 
+    /// Adjust the offset to keep the wrapped widget's cursor visible
+    ///
+    /// This is only done when the cursor moves, so that it can still be
+    /// scrolled out of view.
+    fn follow_cursor(&self, dim: Dim) {
+        let cursor = self.wrapped.cursor();
+        if cursor == self.cursor.get() {
+            return;
+        }
+        self.cursor.set(cursor);
+        if let Some(cursor) = cursor {
+            let mut offset = self.offset.get();
+            if cursor.row < offset.row {
+                offset.row = cursor.row;
+            } else if cursor.row >= offset.row + dim.height {
+                offset.row = cursor.row + 1 - dim.height;
+            }
+            if cursor.col < offset.col {
+                offset.col = cursor.col;
+            } else if cursor.col >= offset.col + dim.width {
+                offset.col = cursor.col + 1 - dim.width;
+            }
+            self.offset.set(offset);
+        }
+    }
+
     /// Handle mouse button down events
     fn mouse_button_down(
         &self,
@@ -381,7 +410,6 @@ impl<W: Widget> Widget for ScrollView<W> {
     /// Draw the widget
     fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
         assert_eq!(offset, Pos::default(), "FIXME");
-        let offset = self.offset.get();
         let mut width = cells.width();
         let mut height = cells.height();
         debug_assert!(width > 0);
@@ -392,6 +420,8 @@ impl<W: Widget> Widget for ScrollView<W> {
         if self.h_bar.is_some() {
             height -= 1;
         }
+        self.follow_cursor(Dim::new(width, height));
+        let offset = self.offset.get();
         let width_bounds = self.wrapped.width_bounds(cells.theme());
         let height_bounds = self
             .wrapped
@@ -400,15 +430,15 @@ impl<W: Widget> Widget for ScrollView<W> {
         {
             self.set_state(State::Disabled);
         }
-        let w_style = cells.theme().style(self.wrapped.style_group());
+        let w_style = cells.style(self.wrapped.style_group());
         if let Some(v_bar) = &self.v_bar {
-            let style = cells.theme().style(v_bar.style_group());
+            let style = cells.style(v_bar.style_group());
             cells.clip(Some(BBox::new(width, 0, 1, height)));
             cells.set_style(style)?;
             v_bar.draw(cells, offset)?;
         }
         if let Some(h_bar) = &self.h_bar {
-            let style = cells.theme().style(h_bar.style_group());
+            let style = cells.style(h_bar.style_group());
             cells.clip(Some(BBox::new(0, height, width, 1)));
             cells.set_style(style)?;
             h_bar.draw(cells, offset)?;
@@ -444,19 +474,49 @@ impl<W: Widget> Widget for ScrollView<W> {
     ) -> Option<Action> {
         let state = self.state.get();
         match (mev, state) {
+            (MouseEvent::ButtonDown(_), State::Disabled)
+            | (MouseEvent::ButtonUp(_), _)
+            | (MouseEvent::Drag(_), State::Disabled)
+            | (MouseEvent::Drag(_), State::Enabled)
+            | (MouseEvent::Drag(_), State::Focused) => self
+                .wrapped
+                .mouse_event(mev, mods, dim, self.offset.get() + pos),
             (_, State::Disabled) => None,
             (MouseEvent::ButtonDown(_), _) => {
                 self.mouse_button_down(mev, mods, dim, pos)
             }
-            (MouseEvent::ButtonUp(_), _) => self.wrapped.mouse_event(
-                mev,
-                mods,
-                dim,
-                self.offset.get() + pos,
-            ),
             (MouseEvent::ScrollDown(), _) => self.scroll_down(mods, dim),
             (MouseEvent::ScrollUp(), _) => self.scroll_up(mods, dim),
             _ => None,
         }
     }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        self.wrapped.key_event(key, mods)
+    }
+
+    /// Get the text cursor position
+    fn cursor(&self) -> Option<Pos> {
+        let offset = self.offset.get();
+        self.wrapped
+            .cursor()
+            .filter(|c| c.col >= offset.col && c.row >= offset.row)
+            .map(|c| c - offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn horizontal_thumb() {
+        let bar = HorizontalScrollBar::new(8);
+        bar.width.set(100);
+        assert_eq!(bar.thumb_cols(10, Pos::new(0, 0)), (0, 1));
+        assert_eq!(bar.thumb_cols(10, Pos::new(50, 3)), (5, 6));
+        assert_eq!(bar.thumb_offset(10, Pos::new(50, 3), 2), Pos::new(49, 3));
+        assert_eq!(bar.thumb_offset(10, Pos::new(50, 3), 8), Pos::new(51, 3));
+    }
 }
@@ -0,0 +1,139 @@
+// styled.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{StyleGroup, TextStyle, Theme, WidgetGroup};
+use crate::{Result, Widget};
+
+/// Style override widget wrapper
+///
+/// Text styles for one or more [StyleGroup]s are overridden while drawing
+/// the wrapped widget (and anything it wraps).  Other groups fall back to
+/// any enclosing overrides, then to the [Theme].
+///
+/// ```rust
+/// use semtext::text::{Color, Intensity, StyleGroup, TextStyle};
+/// use semtext::widget::Label;
+/// use semtext::Widget;
+///
+/// let red = TextStyle::default().with_foreground(Color::Red(Intensity::Bright));
+/// let button = Label::new("Stop")
+///     .into_button()
+///     .with_style_override(StyleGroup::Enabled, red);
+/// ```
+///
+/// [StyleGroup]: ../text/enum.StyleGroup.html
+/// [Theme]: ../text/struct.Theme.html
+pub struct Styled<W: Widget> {
+    /// Wrapped widget
+    wrapped: W,
+    /// Style overrides
+    overrides: Vec<(StyleGroup, TextStyle)>,
+}
+
+impl<W: Widget> Styled<W> {
+    /// Create a new style override wrapper
+    pub fn new(wrapped: W) -> Self {
+        let overrides = vec![];
+        Self { wrapped, overrides }
+    }
+
+    /// Get the wrapped widget
+    pub fn wrapped(&self) -> &W {
+        &self.wrapped
+    }
+
+    /// Override the style for a style group
+    pub fn with_style_override(
+        mut self,
+        group: StyleGroup,
+        style: TextStyle,
+    ) -> Self {
+        self.overrides.retain(|(g, _)| *g != group);
+        self.overrides.push((group, style));
+        self
+    }
+}
+
+impl<W: Widget> Widget for Styled<W> {
+    /// Get the widget group
+    fn widget_group(&self) -> WidgetGroup {
+        self.wrapped.widget_group()
+    }
+
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        self.wrapped.style_group()
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, theme: &Theme) -> LengthBound {
+        self.wrapped.width_bounds(theme)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, theme: &Theme, width: u16) -> LengthBound {
+        self.wrapped.height_bounds(theme, width)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let len = cells.push_overrides(&self.overrides);
+        let style = cells.style(self.wrapped.style_group());
+        let res = cells
+            .set_style(style)
+            .and_then(|_| self.wrapped.draw(cells, offset));
+        cells.restore_overrides(len);
+        res
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.wrapped.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        mods: ModKeys,
+        dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        self.wrapped.mouse_event(mev, mods, dim, pos)
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        self.wrapped.key_event(key, mods)
+    }
+
+    /// Get the text cursor position
+    fn cursor(&self) -> Option<Pos> {
+        self.wrapped.cursor()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::{Color, Intensity};
+    use crate::widget::Label;
+
+    #[test]
+    fn overrides() {
+        let red =
+            TextStyle::default().with_foreground(Color::Red(Intensity::Normal));
+        let blue = red.with_foreground(Color::Blue(Intensity::Normal));
+        let styled = Label::new("x")
+            .with_style_override(StyleGroup::Enabled, red)
+            .with_style_override(StyleGroup::Primary, red)
+            .with_style_override(StyleGroup::Enabled, blue);
+        assert_eq!(
+            styled.overrides,
+            [(StyleGroup::Primary, red), (StyleGroup::Enabled, blue)]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+// switch.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{Action, FocusEvent, KeyPress, ModKeys, MouseEvent, NavKey};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{StyleGroup, Theme};
+use crate::widget::ButtonState;
+use crate::{Result, Widget};
+use std::cell::Cell;
+use unicode_width::UnicodeWidthStr;
+
+/// Toggle switch widget
+///
+/// Clicking, or pressing `Space` / `Enter` while focused, toggles the switch
+/// and produces an `Action::Toggled`.
+pub struct Switch {
+    /// Label text
+    label: String,
+    /// Button state
+    button: ButtonState,
+    /// Switched on
+    on: Cell<bool>,
+}
+
+impl Switch {
+    /// Create a new toggle switch widget (off)
+    pub fn new(label: &str) -> Self {
+        let label = label.to_string();
+        let button = ButtonState::new();
+        let on = Cell::new(false);
+        Self { label, button, on }
+    }
+
+    /// Set the initial state
+    pub fn with_on(self, on: bool) -> Self {
+        self.on.set(on);
+        self
+    }
+
+    /// Check if switched on
+    pub fn is_on(&self) -> bool {
+        self.on.get()
+    }
+
+    /// Switch on or off
+    pub fn set_on(&self, on: bool) {
+        self.on.set(on);
+    }
+
+    /// Disable the switch
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the switch
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Get the style group of the track
+    fn track_group(&self) -> StyleGroup {
+        match (self.button.style_group(), self.on.get()) {
+            (StyleGroup::Disabled, _) => StyleGroup::Disabled,
+            (_, true) => StyleGroup::Primary,
+            (_, false) => StyleGroup::Muted,
+        }
+    }
+
+    /// Toggle the switch
+    fn toggle(&self) -> Option<Action> {
+        let on = !self.on.get();
+        self.on.set(on);
+        Some(Action::Toggled(on))
+    }
+}
+
+impl Widget for Switch {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        self.button.style_group()
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        let w = (self.label.width() as u16).saturating_add(4);
+        LengthBound::new(w..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        LengthBound::new(1..=1)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        if offset.row == 0 {
+            let style = cells.style(self.style_group());
+            cells.move_to(0, 0)?;
+            cells.set_style(cells.style(self.track_group()))?;
+            if self.on.get() {
+                cells.print_str("━━●")?;
+            } else {
+                cells.print_str("○━━")?;
+            }
+            cells.set_style(style)?;
+            cells.print_char(' ')?;
+            cells.print_str(&self.label)?;
+        }
+        Ok(())
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        _pos: Pos,
+    ) -> Option<Action> {
+        if self.button.is_clicked(mev) {
+            self.toggle()
+        } else {
+            self.button.mouse_event(mev)
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        match (key, mods) {
+            (KeyPress::Character(' '), ModKeys::Empty)
+            | (KeyPress::Navigation(NavKey::Enter), ModKeys::Empty)
+                if self.button.is_focused() =>
+            {
+                self.toggle()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widget::button::click;
+
+    #[test]
+    fn toggle() {
+        let sw = Switch::new("Switch");
+        assert_eq!(click(&sw), Some(Action::Toggled(true)));
+        assert_eq!(click(&sw), Some(Action::Toggled(false)));
+        let space = KeyPress::Character(' ');
+        let enter = KeyPress::Navigation(NavKey::Enter);
+        assert_eq!(
+            sw.key_event(space, ModKeys::Empty),
+            Some(Action::Toggled(true))
+        );
+        assert_eq!(
+            sw.key_event(enter, ModKeys::Empty),
+            Some(Action::Toggled(false))
+        );
+        sw.focus(FocusEvent::Take);
+        assert_eq!(sw.key_event(space, ModKeys::Empty), None);
+        assert!(!sw.is_on());
+    }
+
+    #[test]
+    fn disabled() {
+        let sw = Switch::new("Switch").with_on(true);
+        assert_eq!(sw.track_group(), StyleGroup::Primary);
+        sw.disable();
+        assert_eq!(click(&sw), None);
+        assert!(sw.is_on());
+        assert_eq!(sw.style_group(), StyleGroup::Disabled);
+        assert_eq!(sw.track_group(), StyleGroup::Disabled);
+        sw.enable();
+        sw.set_on(false);
+        assert_eq!(sw.track_group(), StyleGroup::Muted);
+    }
+}
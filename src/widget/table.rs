@@ -0,0 +1,677 @@
+// table.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent,
+};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{fit_str, Align, Direction, Outline, StyleGroup, Theme};
+use crate::widget::{list_style_group, nav_index, ButtonState, RowScroll};
+use crate::{Result, Widget};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Source of table cells
+///
+/// Cells are only requested when needed, such as for visible rows.
+pub trait TableSource {
+    /// Get the number of rows
+    fn rows(&self) -> usize;
+
+    /// Get the text of a cell
+    fn cell(&self, row: usize, col: usize) -> Cow<'_, str>;
+}
+
+impl<T: AsRef<str>> TableSource for Vec<Vec<T>> {
+    fn rows(&self) -> usize {
+        self.len()
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Cow<'_, str> {
+        self[row]
+            .get(col)
+            .map_or(Cow::Borrowed(""), |c| Cow::Borrowed(c.as_ref()))
+    }
+}
+
+/// Table column definition
+#[derive(Clone, Debug)]
+pub struct Column {
+    /// Column title
+    title: String,
+    /// Width bounds
+    width: LengthBound,
+    /// Cell alignment
+    align: Align,
+}
+
+/// Table sort order
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortOrder {
+    /// Ascending order
+    Ascending,
+    /// Descending order
+    Descending,
+}
+
+/// Table widget
+///
+/// A header row with column titles stays visible while scrolling, such as
+/// within a [ScrollView].  Clicking a header sorts rows by that column
+/// (clicking again reverses the order), and dragging the separator after a
+/// header resizes its column.
+///
+/// While focused, `Up` / `Down`, `PageUp` / `PageDown` and `Home` / `End`
+/// move the selected row.  Selecting a row produces `Action::Selected`, with
+/// the row index within the [TableSource].
+///
+/// [ScrollView]: struct.ScrollView.html
+/// [TableSource]: trait.TableSource.html
+pub struct Table<S: TableSource> {
+    /// Cell source
+    source: S,
+    /// Column definitions
+    columns: Vec<Column>,
+    /// Separator outline
+    outline: Outline,
+    /// Button state
+    button: ButtonState,
+    /// Column widths (from last draw)
+    widths: RefCell<Vec<u16>>,
+    /// Column widths set by resizing
+    resized: RefCell<Vec<Option<u16>>>,
+    /// Sort column and order
+    sort: Cell<Option<(usize, SortOrder)>>,
+    /// Source row indices, in display order
+    order: RefCell<Vec<usize>>,
+    /// Selected display row
+    selected: Cell<Option<usize>>,
+    /// Body row scrolling state
+    scroll: RowScroll,
+    /// Column being resized
+    resizing: Cell<Option<usize>>,
+}
+
+/// Sort key of cell text
+#[derive(PartialEq, PartialOrd)]
+enum CellKey<'a> {
+    /// Number (not NaN)
+    Number(f64),
+    /// Text, including NaN
+    Text(&'a str),
+}
+
+impl<'a> CellKey<'a> {
+    /// Get the sort key of cell text
+    fn new(text: &'a str) -> Self {
+        match text.trim().parse::<f64>() {
+            Ok(num) if !num.is_nan() => CellKey::Number(num),
+            _ => CellKey::Text(text),
+        }
+    }
+}
+
+/// Compare cell text
+///
+/// Numbers are sorted numerically, before any other text.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    // Never None, since NaN is text
+    CellKey::new(a)
+        .partial_cmp(&CellKey::new(b))
+        .unwrap_or(Ordering::Equal)
+}
+
+impl Column {
+    /// Create a new column
+    pub fn new(title: &str) -> Self {
+        let title = title.to_string();
+        let width = LengthBound::new(1..);
+        let align = Align::default();
+        Self {
+            title,
+            width,
+            align,
+        }
+    }
+
+    /// Set the width bounds
+    pub fn with_width(mut self, width: LengthBound) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the cell alignment
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Get the minimum width, including title
+    fn minimum(&self) -> u16 {
+        let title = fit_str(&self.title, u16::MAX).1;
+        self.width.minimum().max(title.min(self.maximum()))
+    }
+
+    /// Get the maximum width (inclusive)
+    fn maximum(&self) -> u16 {
+        self.width.maximum().saturating_sub(1).max(1)
+    }
+}
+
+impl<S: TableSource> Table<S> {
+    /// Create a new table widget
+    pub fn new(source: S, columns: Vec<Column>) -> Self {
+        let outline = Outline::default();
+        let button = ButtonState::new();
+        let widths = RefCell::new(vec![]);
+        let resized = RefCell::new(vec![None; columns.len()]);
+        let sort = Cell::new(None);
+        let order = RefCell::new((0..source.rows()).collect());
+        let selected = Cell::new(None);
+        let scroll = RowScroll::default();
+        let resizing = Cell::new(None);
+        Self {
+            source,
+            columns,
+            outline,
+            button,
+            widths,
+            resized,
+            sort,
+            order,
+            selected,
+            scroll,
+            resizing,
+        }
+    }
+
+    /// Set the column separator outline
+    pub fn with_outline(mut self, outline: Outline) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// Get the cell source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get the selected row index (within the source)
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+            .get()
+            .and_then(|i| self.order.borrow().get(i).copied())
+    }
+
+    /// Select a row (index within the source)
+    pub fn set_selected(&self, row: Option<usize>) {
+        let pos =
+            row.and_then(|r| self.order.borrow().iter().position(|i| *i == r));
+        self.selected.set(pos);
+    }
+
+    /// Get the sort column and order
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort.get()
+    }
+
+    /// Sort rows by a column
+    pub fn set_sort(&self, col: usize, order: SortOrder) {
+        if col >= self.columns.len() {
+            return;
+        }
+        let selected = self.selected();
+        self.sort.set(Some((col, order)));
+        let mut rows: Vec<usize> = (0..self.source.rows()).collect();
+        rows.sort_by(|a, b| {
+            let ord = compare_cells(
+                &self.source.cell(*a, col),
+                &self.source.cell(*b, col),
+            );
+            match order {
+                SortOrder::Ascending => ord,
+                SortOrder::Descending => ord.reverse(),
+            }
+        });
+        *self.order.borrow_mut() = rows;
+        self.set_selected(selected);
+    }
+
+    /// Reload rows from the source, after it changes
+    pub fn reload(&self) {
+        let selected = self.selected();
+        match self.sort.get() {
+            Some((col, order)) => self.set_sort(col, order),
+            None => {
+                *self.order.borrow_mut() = (0..self.source.rows()).collect()
+            }
+        }
+        self.set_selected(selected);
+    }
+
+    /// Disable the table
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the table
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Get the number of rows, including header
+    fn total_rows(&self) -> u16 {
+        self.order
+            .borrow()
+            .len()
+            .saturating_add(1)
+            .min(usize::from(u16::MAX)) as u16
+    }
+
+    /// Lay out column widths within a total width
+    fn layout_widths(&self, width: u16) -> Vec<u16> {
+        let resized = self.resized.borrow();
+        let mut widths: Vec<u16> = self
+            .columns
+            .iter()
+            .zip(resized.iter())
+            .map(|(c, r)| r.unwrap_or_else(|| c.minimum()))
+            .collect();
+        let seps = widths.len().saturating_sub(1) as u16;
+        let mut extra = width
+            .saturating_sub(seps)
+            .saturating_sub(widths.iter().sum());
+        // Distribute extra space among columns which can grow
+        while extra > 0 {
+            let mut grew = false;
+            for (i, w) in widths.iter_mut().enumerate() {
+                if extra > 0
+                    && resized[i].is_none()
+                    && *w < self.columns[i].maximum()
+                {
+                    *w += 1;
+                    extra -= 1;
+                    grew = true;
+                }
+            }
+            if !grew {
+                break;
+            }
+        }
+        widths
+    }
+
+    /// Get the column at a header position
+    ///
+    /// Returns the column index, and `true` if the position is on the
+    /// separator after it.
+    fn column_at(&self, col: u16) -> Option<(usize, bool)> {
+        let widths = self.widths.borrow();
+        let mut start = 0;
+        for (i, w) in widths.iter().enumerate() {
+            let end = start + w;
+            if col < end {
+                return Some((i, false));
+            }
+            if col == end && i + 1 < widths.len() {
+                return Some((i, true));
+            }
+            start = end + 1;
+        }
+        None
+    }
+
+    /// Get the starting column of a column
+    fn column_start(&self, index: usize) -> u16 {
+        self.widths.borrow()[..index].iter().map(|w| w + 1).sum()
+    }
+
+    /// Get the range of visible display rows for a scroll offset
+    ///
+    /// The header is always drawn, so one less row is visible.
+    fn visible(&self, offset: Pos, rows: u16) -> Range<usize> {
+        self.scroll.visible(
+            offset,
+            rows.saturating_sub(1),
+            self.order.borrow().len(),
+            self.selected.get(),
+            usize::from,
+        )
+    }
+
+    /// Select a display row
+    fn select(&self, pos: usize) -> Option<Action> {
+        if self.selected.get() == Some(pos) {
+            return None;
+        }
+        self.selected.set(Some(pos));
+        self.selected().map(Action::Selected)
+    }
+
+    /// Handle a mouse button down event on the header
+    fn header_down(&self, col: u16) -> Option<Action> {
+        match self.column_at(col)? {
+            (i, true) => {
+                self.resizing.set(Some(i));
+                None
+            }
+            (i, false) => {
+                let order = match self.sort.get() {
+                    Some((c, SortOrder::Ascending)) if c == i => {
+                        SortOrder::Descending
+                    }
+                    _ => SortOrder::Ascending,
+                };
+                self.set_sort(i, order);
+                Some(Action::Redraw())
+            }
+        }
+    }
+
+    /// Resize a column by dragging its separator
+    fn resize(&self, index: usize, col: u16) -> Option<Action> {
+        let start = self.column_start(index);
+        let column = &self.columns[index];
+        let width = col
+            .saturating_sub(start)
+            .max(column.width.minimum())
+            .min(column.maximum())
+            .max(1);
+        if self.widths.borrow()[index] == width {
+            return None;
+        }
+        self.resized.borrow_mut()[index] = Some(width);
+        self.widths.borrow_mut()[index] = width;
+        Some(Action::Redraw())
+    }
+
+    /// Draw one row of cells
+    fn draw_row<'a, F>(
+        &'a self,
+        cells: &mut Cells,
+        row: u16,
+        text: F,
+    ) -> Result<()>
+    where
+        F: Fn(usize) -> (Cow<'a, str>, Align),
+    {
+        let widths = self.widths.borrow();
+        let mut col = 0;
+        for (i, w) in widths.iter().enumerate() {
+            if i > 0 {
+                cells.move_to(col, row)?;
+                cells.print_char(self.outline.left())?;
+                col += 1;
+            }
+            let (txt, align) = text(i);
+            let (txt, tw) = fit_str(&txt, *w);
+            let pad = align.offset(Direction::LeftToRight, *w, tw);
+            cells.move_to(col, row)?;
+            cells.print_str(&" ".repeat(usize::from(pad)))?;
+            cells.print_str(txt)?;
+            cells.print_str(&" ".repeat(usize::from(w - pad - tw)))?;
+            col += w;
+        }
+        Ok(())
+    }
+}
+
+impl<S: TableSource> Widget for Table<S> {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        list_style_group(&self.button)
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        let seps = self.columns.len().saturating_sub(1) as u16;
+        let min = self
+            .columns
+            .iter()
+            .fold(seps, |w, c| w.saturating_add(c.minimum()));
+        let max = self
+            .columns
+            .iter()
+            .fold(seps, |w, c| w.saturating_add(c.maximum()));
+        LengthBound::new(min..=max.max(min))
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        LengthBound::new(self.total_rows()..)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        *self.widths.borrow_mut() = self.layout_widths(cells.width());
+        let style = cells.style(self.style_group());
+        let header_style = cells.style(StyleGroup::Primary);
+        let selected_style = cells.style(StyleGroup::Focused);
+        let sort = self.sort.get();
+        cells.set_style(header_style)?;
+        self.draw_row(cells, 0, |i| {
+            let column = &self.columns[i];
+            let title = match sort {
+                Some((c, SortOrder::Ascending)) if c == i => {
+                    Cow::Owned(format!("{}▲", column.title))
+                }
+                Some((c, SortOrder::Descending)) if c == i => {
+                    Cow::Owned(format!("{}▼", column.title))
+                }
+                _ => Cow::Borrowed(&column.title[..]),
+            };
+            (title, column.align)
+        })?;
+        let visible = self.visible(offset, cells.height());
+        let top = visible.start;
+        let selected = self.selected.get();
+        for pos in visible {
+            let row = self.order.borrow()[pos];
+            if selected == Some(pos) {
+                cells.set_style(selected_style)?;
+            } else {
+                cells.set_style(style)?;
+            }
+            let r = (pos - top + 1) as u16;
+            self.draw_row(cells, r, |i| {
+                (self.source.cell(row, i), self.columns[i].align)
+            })?;
+        }
+        cells.set_style(style)
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        let redraw = self.button.mouse_event(mev);
+        let row = pos.row.saturating_sub(self.scroll.offset().row);
+        match mev {
+            MouseEvent::ButtonDown(_) if !self.button.is_focused() => {
+                self.resizing.set(None);
+                redraw
+            }
+            MouseEvent::ButtonDown(MouseButton::Left) => {
+                // A drag may have ended outside the table
+                self.resizing.set(None);
+                if row == 0 {
+                    return self.header_down(pos.col).or(redraw);
+                }
+                let pos = self.scroll.top() + usize::from(row - 1);
+                if pos < self.order.borrow().len() {
+                    self.select(pos).or(redraw)
+                } else {
+                    redraw
+                }
+            }
+            MouseEvent::Drag(Some(MouseButton::Left)) => {
+                match self.resizing.get() {
+                    Some(index) => self.resize(index, pos.col),
+                    None => redraw,
+                }
+            }
+            MouseEvent::ButtonUp(_) => {
+                self.resizing.set(None);
+                redraw
+            }
+            _ => redraw,
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if !self.button.is_focused() {
+            return None;
+        }
+        match (key, mods) {
+            (KeyPress::Navigation(nav), ModKeys::Empty) => {
+                let len = self.order.borrow().len();
+                let page = usize::from(self.scroll.rows());
+                let pos = match self.selected.get() {
+                    Some(sel) => nav_index(nav, sel, len, page)?,
+                    None => nav_index(nav, self.scroll.top(), len, 0)?,
+                };
+                self.select(pos).or(Some(Action::Redraw()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the row of the selected item
+    ///
+    /// This is the row below the header, so that a [ScrollView] scrolling
+    /// down keeps the selected row visible.  When scrolling up, the table
+    /// itself moves the selected row to the top, since the header covers
+    /// the first row of the scroll offset.
+    ///
+    /// [ScrollView]: struct.ScrollView.html
+    fn cursor(&self) -> Option<Pos> {
+        self.selected.get().map(|sel| {
+            let row = sel.saturating_add(1).min(usize::from(u16::MAX));
+            Pos::new(0, row as u16)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::NavKey;
+
+    fn table() -> Table<Vec<Vec<&'static str>>> {
+        let rows = vec![
+            vec!["carrot", "10"],
+            vec!["apple", "9"],
+            vec!["banana", "100"],
+            vec!["date", "2"],
+        ];
+        let columns = vec![
+            Column::new("Name").with_width(LengthBound::new(4..=8)),
+            Column::new("Qty").with_align(Align::End),
+        ];
+        Table::new(rows, columns)
+    }
+
+    #[test]
+    fn widths() {
+        let table = table();
+        let theme = Theme::default();
+        assert_eq!(table.width_bounds(&theme), LengthBound::new(8..));
+        assert_eq!(table.height_bounds(&theme, 8), LengthBound::new(5..));
+        assert_eq!(table.layout_widths(10), vec![5, 4]);
+        assert_eq!(table.layout_widths(20), vec![8, 11]);
+        *table.widths.borrow_mut() = table.layout_widths(20);
+        assert_eq!(table.column_at(3), Some((0, false)));
+        assert_eq!(table.column_at(8), Some((0, true)));
+        assert_eq!(table.column_at(9), Some((1, false)));
+        assert_eq!(table.column_at(20), None);
+    }
+
+    #[test]
+    fn sort() {
+        let table = table();
+        table.set_selected(Some(0));
+        table.set_sort(1, SortOrder::Ascending);
+        assert_eq!(*table.order.borrow(), vec![3, 1, 0, 2]);
+        table.set_sort(0, SortOrder::Descending);
+        assert_eq!(*table.order.borrow(), vec![3, 0, 2, 1]);
+        assert_eq!(table.selected(), Some(0));
+        assert_eq!(table.selected.get(), Some(1));
+    }
+
+    #[test]
+    fn sort_mixed() {
+        let mut cells = vec!["1a", "NaN", "10", "2", "-0", "0", "b", "inf"];
+        cells.sort_by(|a, b| compare_cells(a, b));
+        assert_eq!(cells, ["-0", "0", "2", "10", "inf", "1a", "NaN", "b"]);
+        let rows = vec![vec!["2"], vec!["10"], vec!["1a"], vec!["NaN"]];
+        let table = Table::new(rows, vec![Column::new("N")]);
+        table.set_sort(0, SortOrder::Ascending);
+        assert_eq!(*table.order.borrow(), vec![0, 1, 2, 3]);
+        table.set_sort(0, SortOrder::Descending);
+        assert_eq!(*table.order.borrow(), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn mouse() {
+        let table = table();
+        *table.widths.borrow_mut() = table.layout_widths(20);
+        let dim = Dim::new(20, 5);
+        let down = MouseEvent::ButtonDown(MouseButton::Left);
+        let drag = MouseEvent::Drag(Some(MouseButton::Left));
+        let up = MouseEvent::ButtonUp(MouseButton::Left);
+        let mev = |mev, col, row| {
+            table.mouse_event(mev, ModKeys::Empty, dim, Pos::new(col, row))
+        };
+        table.focus(FocusEvent::Offer);
+        assert_eq!(mev(down, 10, 0), Some(Action::Redraw()));
+        assert_eq!(table.sort(), Some((1, SortOrder::Ascending)));
+        mev(up, 10, 0);
+        assert_eq!(mev(down, 10, 0), Some(Action::Redraw()));
+        assert_eq!(table.sort(), Some((1, SortOrder::Descending)));
+        mev(up, 10, 0);
+        assert_eq!(mev(down, 1, 2), Some(Action::Selected(0)));
+        mev(up, 1, 2);
+        mev(down, 8, 0);
+        assert_eq!(mev(drag, 5, 0), Some(Action::Redraw()));
+        assert_eq!(*table.widths.borrow(), vec![5, 11]);
+        assert_eq!(mev(drag, 2, 0), Some(Action::Redraw()));
+        assert_eq!(mev(drag, 3, 0), None);
+        mev(up, 3, 0);
+        assert_eq!(mev(drag, 7, 0), None);
+        assert_eq!(table.layout_widths(20), vec![4, 15]);
+        // Drag ending outside the table
+        mev(down, 4, 0);
+        assert_eq!(mev(down, 1, 3), Some(Action::Selected(1)));
+        assert_eq!(mev(drag, 9, 3), None);
+        assert_eq!(table.layout_widths(20), vec![4, 15]);
+    }
+
+    #[test]
+    fn keys() {
+        let table = table();
+        table.visible(Pos::default(), 3);
+        table.focus(FocusEvent::Offer);
+        let key =
+            |nav| table.key_event(KeyPress::Navigation(nav), ModKeys::Empty);
+        assert_eq!(key(NavKey::Down), Some(Action::Selected(1)));
+        assert_eq!(key(NavKey::PageDown), Some(Action::Selected(3)));
+        assert_eq!(table.cursor(), Some(Pos::new(0, 4)));
+        assert_eq!(table.visible(Pos::new(0, 2), 3), 2..4);
+        assert_eq!(key(NavKey::Home), Some(Action::Selected(0)));
+        assert_eq!(table.cursor(), Some(Pos::new(0, 1)));
+        assert_eq!(table.visible(Pos::new(0, 1), 3), 0..2);
+    }
+}
@@ -0,0 +1,599 @@
+// tabs.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent, NavKey,
+};
+use crate::layout::{BBox, Cells, Dim, LengthBound, Pos};
+use crate::text::{fit_str, Outline, StyleGroup, Theme};
+use crate::{Result, Widget};
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Height of tab bar
+const BAR_ROWS: u16 = 2;
+
+/// One tab, with title and content
+struct Tab {
+    /// Tab title
+    title: String,
+    /// Content widget
+    content: Box<dyn Widget>,
+}
+
+/// Part of the tab bar
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Part {
+    /// Scroll to previous tabs
+    Prev,
+    /// Scroll to next tabs
+    Next,
+    /// Separator between tabs
+    Sep,
+    /// Tab title
+    Tab(usize),
+    /// Tab close button
+    Close(usize),
+}
+
+/// Tabs widget
+///
+/// A tab bar with titles is drawn above the content of the selected tab.
+/// Tabs are switched by clicking a title, or with `Ctrl` + `PageUp` /
+/// `PageDown` while focused, producing `Action::TabSelected`.  Closeable
+/// tabs have a `×` button, which produces `Action::TabClosed`.  Clicking it on
+/// the selected tab also selects the next one, without a separate
+/// `Action::TabSelected`, so check [selected] after `Action::TabClosed`.
+///
+/// When the titles do not fit, arrows at either end of the bar scroll them.
+///
+/// [selected]: struct.Tabs.html#method.selected
+pub struct Tabs {
+    /// All tabs
+    tabs: RefCell<Vec<Tab>>,
+    /// Tab bar outline
+    outline: Outline,
+    /// Tabs can be closed
+    closeable: bool,
+    /// Selected tab
+    selected: Cell<usize>,
+    /// First tab in bar (when scrolled)
+    scroll: Cell<usize>,
+    /// Scroll to selected tab on next draw
+    follow: Cell<bool>,
+    /// Widget focused
+    focused: Cell<bool>,
+    /// Widget width (from last draw)
+    width: Cell<u16>,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tabs {
+    /// Create a new tabs widget
+    pub fn new() -> Self {
+        let tabs = RefCell::new(vec![]);
+        let outline = Outline::default();
+        let closeable = false;
+        let selected = Cell::new(0);
+        let scroll = Cell::new(0);
+        let follow = Cell::new(false);
+        let focused = Cell::new(false);
+        let width = Cell::new(0);
+        Self {
+            tabs,
+            outline,
+            closeable,
+            selected,
+            scroll,
+            follow,
+            focused,
+            width,
+        }
+    }
+
+    /// Add a tab
+    pub fn with_tab<W: Widget + 'static>(
+        self,
+        title: &str,
+        content: W,
+    ) -> Self {
+        self.add_tab(title, Box::new(content));
+        self
+    }
+
+    /// Set the tab bar outline
+    pub fn with_outline(mut self, outline: Outline) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// Make tabs closeable
+    pub fn with_closeable(mut self, closeable: bool) -> Self {
+        self.closeable = closeable;
+        self
+    }
+
+    /// Add a tab
+    pub fn add_tab(&self, title: &str, content: Box<dyn Widget>) {
+        let title = title.to_string();
+        self.tabs.borrow_mut().push(Tab { title, content });
+    }
+
+    /// Get the number of tabs
+    pub fn len(&self) -> usize {
+        self.tabs.borrow().len()
+    }
+
+    /// Check if there are no tabs
+    pub fn is_empty(&self) -> bool {
+        self.tabs.borrow().is_empty()
+    }
+
+    /// Get the selected tab index
+    pub fn selected(&self) -> usize {
+        self.selected.get()
+    }
+
+    /// Select a tab
+    ///
+    /// While the tabs widget is focused, focus moves to the content of the
+    /// selected tab.
+    pub fn set_selected(&self, index: usize) {
+        let selected = self.selected.get();
+        if index < self.len() && index != selected {
+            let tabs = self.tabs.borrow();
+            tabs[selected].content.focus(FocusEvent::Take);
+            self.selected.set(index);
+            if self.focused.get() {
+                tabs[index].content.focus(FocusEvent::Offer);
+            }
+        }
+        self.follow.set(true);
+    }
+
+    /// Close a tab
+    ///
+    /// If the selected tab is closed, the next tab is selected (or the
+    /// previous one, if it was last), producing `Action::TabSelected`.
+    pub fn close(&self, index: usize) -> Option<Action> {
+        if index >= self.len() {
+            return None;
+        }
+        let selected = self.selected.get();
+        let tab = self.tabs.borrow_mut().remove(index);
+        if selected > index || selected >= self.len() {
+            self.selected.set(selected.saturating_sub(1));
+        }
+        self.scroll
+            .set(self.scroll.get().min(self.len().saturating_sub(1)));
+        self.follow.set(true);
+        if index != selected || self.is_empty() {
+            return None;
+        }
+        let selected = self.selected.get();
+        if self.focused.get() {
+            tab.content.focus(FocusEvent::Take);
+            self.tabs.borrow()[selected]
+                .content
+                .focus(FocusEvent::Offer);
+        }
+        Some(Action::TabSelected(selected))
+    }
+
+    /// Get the width of a tab title
+    fn tab_width(&self, tab: &Tab) -> u16 {
+        let close = if self.closeable { 2 } else { 0 };
+        let width = tab.title.width().min(usize::from(u16::MAX - 4)) as u16;
+        width + close + 2
+    }
+
+    /// Lay out the tab bar
+    ///
+    /// Returns column ranges of each part, in order.
+    fn layout(&self, width: u16) -> Vec<(Range<u16>, Part)> {
+        let tabs = self.tabs.borrow();
+        let widths: Vec<u16> = tabs.iter().map(|t| self.tab_width(t)).collect();
+        let total = widths
+            .iter()
+            .fold(widths.len().saturating_sub(1) as u16, |t, w| {
+                t.saturating_add(*w)
+            });
+        let overflow = total > width && width > 2;
+        let mut parts = vec![];
+        let (scroll, mut col, end) = if overflow {
+            parts.push((0..1, Part::Prev));
+            (self.scroll.get().min(tabs.len() - 1), 1, width - 1)
+        } else {
+            (0, 0, width)
+        };
+        for (i, w) in widths.iter().enumerate().skip(scroll) {
+            if i > scroll {
+                if col + 1 + w > end {
+                    break;
+                }
+                parts.push((col..col + 1, Part::Sep));
+                col += 1;
+            }
+            // First tab is truncated if necessary
+            let w = (*w).min(end - col);
+            parts.push((col..col + w, Part::Tab(i)));
+            if self.closeable && w >= 4 {
+                let x = col + w - 2;
+                parts.push((x..x + 1, Part::Close(i)));
+            }
+            col += w;
+        }
+        if overflow {
+            parts.push((width - 1..width, Part::Next));
+        }
+        parts
+    }
+
+    /// Scroll the tab bar so the selected tab is visible
+    fn follow_selected(&self, width: u16) {
+        let selected = self.selected.get();
+        if selected < self.scroll.get() {
+            self.scroll.set(selected);
+        }
+        while self.scroll.get() < selected
+            && !self
+                .layout(width)
+                .iter()
+                .any(|(_, p)| *p == Part::Tab(selected))
+        {
+            self.scroll.set(self.scroll.get() + 1);
+        }
+    }
+
+    /// Check if the tab bar can scroll to later tabs
+    fn can_scroll_next(&self, width: u16) -> bool {
+        let last = self.len().saturating_sub(1);
+        !self
+            .layout(width)
+            .iter()
+            .any(|(_, p)| *p == Part::Tab(last))
+    }
+
+    /// Switch to another tab
+    fn switch(&self, index: usize) -> Option<Action> {
+        if index == self.selected.get() || index >= self.len() {
+            return None;
+        }
+        self.set_selected(index);
+        Some(Action::TabSelected(index))
+    }
+
+    /// Handle a mouse button down event on the tab bar
+    fn bar_down(&self, col: u16) -> Option<Action> {
+        let width = self.width.get();
+        let part = self
+            .layout(width)
+            .into_iter()
+            .rev()
+            .find(|(r, _)| r.contains(&col))
+            .map(|(_, p)| p)?;
+        match part {
+            Part::Prev if self.scroll.get() > 0 => {
+                self.scroll.set(self.scroll.get() - 1);
+                Some(Action::Redraw())
+            }
+            Part::Next if self.can_scroll_next(width) => {
+                self.scroll.set(self.scroll.get() + 1);
+                Some(Action::Redraw())
+            }
+            Part::Tab(i) => self.switch(i),
+            Part::Close(i) => {
+                // Any selection change is checked by the app after this
+                self.close(i);
+                Some(Action::TabClosed(i))
+            }
+            _ => None,
+        }
+    }
+
+    /// Draw the tab bar
+    fn draw_bar(&self, cells: &mut Cells) -> Result<()> {
+        let width = cells.width();
+        let style = cells.style(StyleGroup::Enabled);
+        let muted_style = cells.style(StyleGroup::Muted);
+        let selected_style = cells.style(StyleGroup::Primary);
+        let parts = self.layout(width);
+        let tabs = self.tabs.borrow();
+        let selected = self.selected.get();
+        let outline = self.outline;
+        for (range, part) in &parts {
+            cells.move_to(range.start, 0)?;
+            match part {
+                Part::Prev | Part::Next => {
+                    let (ch, enabled) = if *part == Part::Prev {
+                        ('◂', self.scroll.get() > 0)
+                    } else {
+                        ('▸', self.can_scroll_next(width))
+                    };
+                    let st = if enabled { style } else { muted_style };
+                    cells.set_style(st)?;
+                    cells.print_char(ch)?;
+                }
+                Part::Sep => {
+                    cells.set_style(style)?;
+                    cells.print_char(outline.left())?;
+                }
+                Part::Tab(i) => {
+                    let st = if *i == selected {
+                        selected_style
+                    } else {
+                        style
+                    };
+                    cells.set_style(st)?;
+                    let w = range.end - range.start;
+                    let close = if self.closeable { 2 } else { 0 };
+                    let avail = w.saturating_sub(close + 2);
+                    let (title, tw) = fit_str(&tabs[*i].title, avail);
+                    cells.print_char(' ')?;
+                    cells.print_str(title)?;
+                    let pad = w.saturating_sub(tw + 1);
+                    cells.print_str(&" ".repeat(usize::from(pad)))?;
+                }
+                Part::Close(_) => {
+                    cells.print_char('×')?;
+                }
+            }
+        }
+        // Underline, open below the selected tab
+        cells.set_style(style)?;
+        cells.move_to(0, 1)?;
+        let sel = parts.iter().find(|(_, p)| *p == Part::Tab(selected));
+        for col in 0..width {
+            let ch = match sel {
+                Some((r, _)) if r.contains(&col) => ' ',
+                Some((r, _)) if col + 1 == r.start && col > 0 => {
+                    outline.bottom_right(outline)
+                }
+                Some((r, _)) if col == r.end && col + 1 < width => {
+                    outline.bottom_left(outline)
+                }
+                _ => outline.top(),
+            };
+            cells.print_char(ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Tabs {
+    /// Get the width bounds
+    fn width_bounds(&self, theme: &Theme) -> LengthBound {
+        let min = self
+            .tabs
+            .borrow()
+            .iter()
+            .map(|t| t.content.width_bounds(theme).minimum())
+            .max()
+            .unwrap_or(0);
+        LengthBound::new(min.max(3)..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, theme: &Theme, width: u16) -> LengthBound {
+        let min = self
+            .tabs
+            .borrow()
+            .iter()
+            .map(|t| t.content.height_bounds(theme, width).minimum())
+            .max()
+            .unwrap_or(0);
+        LengthBound::new(min.saturating_add(BAR_ROWS)..)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let width = cells.width();
+        let height = cells.height();
+        self.width.set(width);
+        if self.follow.replace(false) {
+            self.follow_selected(width);
+        }
+        self.draw_bar(cells)?;
+        if height <= BAR_ROWS {
+            return Ok(());
+        }
+        let tabs = self.tabs.borrow();
+        if let Some(tab) = tabs.get(self.selected.get()) {
+            cells.narrow(BBox::new(0, BAR_ROWS, width, height - BAR_ROWS));
+            let style = cells.style(tab.content.style_group());
+            cells.set_style(style)?;
+            tab.content.draw(cells, offset)?;
+        }
+        Ok(())
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        let changed = match fev {
+            FocusEvent::Offer => !self.focused.replace(true),
+            FocusEvent::Take => self.focused.replace(false),
+            _ => false,
+        };
+        let tabs = self.tabs.borrow();
+        let action = tabs
+            .get(self.selected.get())
+            .and_then(|tab| tab.content.focus(fev));
+        if changed {
+            action.or(Some(Action::Redraw()))
+        } else {
+            action
+        }
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        mods: ModKeys,
+        dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        if pos.row < BAR_ROWS {
+            return match mev {
+                MouseEvent::ButtonDown(MouseButton::Left) => {
+                    self.bar_down(pos.col)
+                }
+                _ => None,
+            };
+        }
+        let dim = Dim::new(dim.width, dim.height.saturating_sub(BAR_ROWS));
+        let pos = Pos::new(pos.col, pos.row - BAR_ROWS);
+        let tabs = self.tabs.borrow();
+        tabs.get(self.selected.get())
+            .and_then(|tab| tab.content.mouse_event(mev, mods, dim, pos))
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if self.focused.get() && mods == ModKeys::Control {
+            let selected = self.selected.get();
+            match key {
+                KeyPress::Navigation(NavKey::PageUp) if selected > 0 => {
+                    return self.switch(selected - 1);
+                }
+                KeyPress::Navigation(NavKey::PageDown) => {
+                    return self.switch(selected + 1);
+                }
+                _ => (),
+            }
+        }
+        let tabs = self.tabs.borrow();
+        tabs.get(self.selected.get())
+            .and_then(|tab| tab.content.key_event(key, mods))
+    }
+
+    /// Get the text cursor position
+    fn cursor(&self) -> Option<Pos> {
+        let tabs = self.tabs.borrow();
+        tabs.get(self.selected.get())
+            .and_then(|tab| tab.content.cursor())
+            .map(|c| c + Pos::new(0, BAR_ROWS))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widget::{Label, ListView};
+
+    fn tabs() -> Tabs {
+        Tabs::new()
+            .with_tab("One", Label::new("1"))
+            .with_tab("Two", Label::new("2"))
+            .with_tab("Three", Label::new("3"))
+            .with_closeable(true)
+    }
+
+    #[test]
+    fn layout() {
+        let tabs = tabs();
+        assert_eq!(
+            tabs.layout(30),
+            vec![
+                (0..7, Part::Tab(0)),
+                (5..6, Part::Close(0)),
+                (7..8, Part::Sep),
+                (8..15, Part::Tab(1)),
+                (13..14, Part::Close(1)),
+                (15..16, Part::Sep),
+                (16..25, Part::Tab(2)),
+                (23..24, Part::Close(2)),
+            ]
+        );
+        tabs.scroll.set(1);
+        assert_eq!(
+            tabs.layout(20),
+            vec![
+                (0..1, Part::Prev),
+                (1..8, Part::Tab(1)),
+                (6..7, Part::Close(1)),
+                (8..9, Part::Sep),
+                (9..18, Part::Tab(2)),
+                (16..17, Part::Close(2)),
+                (19..20, Part::Next),
+            ]
+        );
+        tabs.set_selected(0);
+        tabs.follow_selected(20);
+        assert_eq!(tabs.scroll.get(), 0);
+        assert!(tabs.can_scroll_next(20));
+        tabs.set_selected(2);
+        tabs.follow_selected(20);
+        assert_eq!(tabs.scroll.get(), 1);
+        assert!(!tabs.can_scroll_next(20));
+    }
+
+    #[test]
+    fn switch() {
+        let tabs = tabs();
+        let page_down = KeyPress::Navigation(NavKey::PageDown);
+        let page_up = KeyPress::Navigation(NavKey::PageUp);
+        assert_eq!(tabs.key_event(page_down, ModKeys::Control), None);
+        tabs.focus(FocusEvent::Offer);
+        assert_eq!(
+            tabs.key_event(page_down, ModKeys::Control),
+            Some(Action::TabSelected(1))
+        );
+        assert_eq!(
+            tabs.key_event(page_up, ModKeys::Control),
+            Some(Action::TabSelected(0))
+        );
+        assert_eq!(tabs.key_event(page_up, ModKeys::Control), None);
+        tabs.width.set(30);
+        let down = MouseEvent::ButtonDown(MouseButton::Left);
+        let dim = Dim::new(30, 5);
+        let click =
+            |col| tabs.mouse_event(down, ModKeys::Empty, dim, Pos::new(col, 0));
+        assert_eq!(click(18), Some(Action::TabSelected(2)));
+        assert_eq!(click(13), Some(Action::TabClosed(1)));
+        assert_eq!(tabs.len(), 2);
+        assert_eq!(tabs.selected(), 1);
+        assert_eq!(click(7), None);
+        assert_eq!(click(2), Some(Action::TabSelected(0)));
+        // Closing the selected tab selects the next one
+        assert_eq!(click(5), Some(Action::TabClosed(0)));
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(tabs.selected(), 0);
+        assert_eq!(tabs.tabs.borrow()[0].title, "Three");
+    }
+
+    #[test]
+    fn focus() {
+        let tabs = Tabs::new()
+            .with_tab("One", ListView::new(vec!["a", "b"]))
+            .with_tab("Two", ListView::new(vec!["c", "d"]))
+            .with_tab("Three", ListView::new(vec!["e", "f"]));
+        let down = KeyPress::Navigation(NavKey::Down);
+        let page_down = KeyPress::Navigation(NavKey::PageDown);
+        tabs.focus(FocusEvent::Offer);
+        assert_eq!(
+            tabs.key_event(page_down, ModKeys::Control),
+            Some(Action::TabSelected(1))
+        );
+        assert_eq!(
+            tabs.key_event(down, ModKeys::Empty),
+            Some(Action::Selected(1))
+        );
+        assert_eq!(tabs.close(0), None);
+        assert_eq!(tabs.selected(), 0);
+        assert_eq!(tabs.close(0), Some(Action::TabSelected(0)));
+        assert_eq!(
+            tabs.key_event(down, ModKeys::Empty),
+            Some(Action::Selected(1))
+        );
+        assert_eq!(tabs.close(0), None);
+        assert!(tabs.is_empty());
+    }
+}
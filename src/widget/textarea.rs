@@ -0,0 +1,556 @@
+// textarea.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent, NavKey,
+};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{grapheme_width, StyleGroup, Theme};
+use crate::widget::{visible_graphemes, EditBuffer};
+use crate::{Result, Widget};
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Text area state
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    /// Text area disabled
+    Disabled,
+    /// Text area enabled
+    Enabled,
+    /// Text area focused
+    Focused,
+}
+
+/// Multi-line text editor widget
+///
+/// Editing keys are the same as [TextInput], with these additions:
+///
+/// Key                          | Edit
+/// -----------------------------|-----
+/// `Up` / `Down`                | Move by row
+/// `PageUp` / `PageDown`        | Move by visible rows
+/// `Home` / `End`               | Move to start / end of row
+/// `Ctrl` + `Home` / `End`      | Move to start / end of text
+/// `Enter`                      | Insert line break
+/// `Ctrl` + `Z`                 | Undo
+/// `Ctrl` + `Y` / `Shift` + `Z` | Redo
+///
+/// Lines are wrapped at the widget width by default.  The height bounds
+/// include every row, so it should be wrapped with a [ScrollView], which
+/// follows the cursor.
+///
+/// [ScrollView]: struct.ScrollView.html
+/// [TextInput]: struct.TextInput.html
+pub struct TextArea {
+    /// Edit buffer
+    buffer: RefCell<EditBuffer>,
+    /// Wrap lines at widget width
+    wrap: bool,
+    /// Text area state
+    state: Cell<State>,
+    /// Visible dimensions (from last draw)
+    dim: Cell<Dim>,
+    /// Goal column for vertical movement
+    goal_col: Cell<Option<u16>>,
+}
+
+/// Get the width of a str in text cells
+fn str_width(st: &str) -> u16 {
+    st.graphemes(true).map(grapheme_width).sum::<usize>() as u16
+}
+
+/// Filter control characters (except newlines) from text
+fn filter_text(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .chars()
+        .filter(|c| *c == '\n' || !c.is_control())
+        .collect()
+}
+
+/// Lay out text into rows
+///
+/// * `text`: Text to lay out
+/// * `width`: Wrap width, or `None` to not wrap
+///
+/// ## Return
+///
+/// Byte range of each row, excluding line breaks.
+fn layout_rows(text: &str, width: Option<u16>) -> Vec<Range<usize>> {
+    let mut rows = vec![];
+    let mut start = 0;
+    for line in text.split('\n') {
+        match width {
+            Some(width) if width > 0 => {
+                wrap_line(line, start, width, &mut rows)
+            }
+            _ => rows.push(start..start + line.len()),
+        }
+        start += line.len() + 1;
+    }
+    rows
+}
+
+/// Wrap one line into rows, breaking after whitespace where possible
+fn wrap_line(
+    line: &str,
+    base: usize,
+    width: u16,
+    rows: &mut Vec<Range<usize>>,
+) {
+    let mut start = 0;
+    let mut col = 0;
+    let mut brk = None;
+    for (i, g) in line.grapheme_indices(true) {
+        let w = grapheme_width(g) as u16;
+        let space = g.chars().all(char::is_whitespace);
+        // Trailing whitespace is allowed to hang past the width
+        if col + w > width && i > start && !space {
+            let end = brk.filter(|b| *b > start).unwrap_or(i);
+            rows.push(base + start..base + end);
+            col = str_width(&line[end..i]);
+            start = end;
+            brk = None;
+        }
+        col += w;
+        if space {
+            brk = Some(i + g.len());
+        }
+    }
+    rows.push(base + start..base + line.len());
+}
+
+impl TextArea {
+    /// Create a new text area widget
+    pub fn new(text: &str) -> Self {
+        let buffer = RefCell::new(EditBuffer::new(&filter_text(text)));
+        let wrap = true;
+        let state = Cell::new(State::Enabled);
+        let dim = Cell::new(Dim::default());
+        let goal_col = Cell::new(None);
+        Self {
+            buffer,
+            wrap,
+            state,
+            dim,
+            goal_col,
+        }
+    }
+
+    /// Set line wrapping
+    ///
+    /// When disabled, long lines extend past the widget width, and can be
+    /// scrolled horizontally with a [ScrollView].
+    ///
+    /// [ScrollView]: struct.ScrollView.html
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Get the text
+    pub fn text(&self) -> String {
+        self.buffer.borrow().text().to_string()
+    }
+
+    /// Set the text
+    ///
+    /// The cursor is moved to the end, and undo history cleared.
+    pub fn set_text(&self, text: &str) {
+        self.buffer.borrow_mut().set_text(&filter_text(text));
+    }
+
+    /// Get the selected text
+    pub fn selected_text(&self) -> Option<String> {
+        self.buffer.borrow().selected_text().map(str::to_string)
+    }
+
+    /// Disable the text area
+    pub fn disable(&self) {
+        self.state.set(State::Disabled);
+    }
+
+    /// Enable the text area
+    pub fn enable(&self) {
+        if self.state.get() == State::Disabled {
+            self.state.set(State::Enabled);
+        }
+    }
+
+    /// Lay out rows at a width
+    fn rows(&self, width: u16) -> Vec<Range<usize>> {
+        let width = if self.wrap { Some(width) } else { None };
+        layout_rows(self.buffer.borrow().text(), width)
+    }
+
+    /// Get the row and column of a buffer offset
+    fn offset_pos(&self, rows: &[Range<usize>], offset: usize) -> Pos {
+        let row = rows.iter().rposition(|r| r.start <= offset).unwrap_or(0);
+        let start = rows.get(row).map_or(0, |r| r.start);
+        let col = str_width(&self.buffer.borrow().text()[start..offset]);
+        Pos::new(col, row as u16)
+    }
+
+    /// Get the buffer offset at a row and column
+    fn pos_offset(&self, rows: &[Range<usize>], pos: Pos) -> usize {
+        let buffer = self.buffer.borrow();
+        let row = usize::from(pos.row).min(rows.len() - 1);
+        let range = rows[row].clone();
+        let mut width = 0;
+        for (i, g) in buffer.text()[range.clone()].grapheme_indices(true) {
+            let w = grapheme_width(g) as u16;
+            if width + w > pos.col {
+                // Round to the nearest grapheme boundary
+                let i = range.start + i;
+                return if pos.col - width > w / 2 {
+                    i + g.len()
+                } else {
+                    i
+                };
+            }
+            width += w;
+        }
+        // Stay before the break of a wrapped row
+        let next = rows.get(row + 1).map(|r| r.start);
+        if next == Some(range.end) && range.end > range.start {
+            buffer.prev_grapheme(range.end)
+        } else {
+            range.end
+        }
+    }
+
+    /// Move the cursor vertically
+    fn move_rows(&self, up: bool, count: u16, extend: bool) {
+        let rows = self.rows(self.dim.get().width);
+        let cursor = self.buffer.borrow().cursor();
+        let pos = self.offset_pos(&rows, cursor);
+        let col = self.goal_col.get().unwrap_or(pos.col);
+        let offset = if up && pos.row == 0 {
+            0
+        } else if !up && usize::from(pos.row) + 1 >= rows.len() {
+            self.buffer.borrow().text().len()
+        } else {
+            let row = if up {
+                pos.row.saturating_sub(count)
+            } else {
+                pos.row.saturating_add(count)
+            };
+            self.pos_offset(&rows, Pos::new(col, row))
+        };
+        self.buffer.borrow_mut().move_to(offset, extend);
+        self.goal_col.set(Some(col));
+    }
+
+    /// Move the cursor to the start or end of its row
+    fn move_row_edge(&self, end: bool, extend: bool) {
+        let rows = self.rows(self.dim.get().width);
+        let cursor = self.buffer.borrow().cursor();
+        let pos = self.offset_pos(&rows, cursor);
+        let col = if end { u16::MAX } else { 0 };
+        let offset = self.pos_offset(&rows, Pos::new(col, pos.row));
+        self.buffer.borrow_mut().move_to(offset, extend);
+    }
+
+    /// Handle a navigation key
+    fn nav_key(&self, key: NavKey, mods: ModKeys) -> Option<Action> {
+        use ModKeys::*;
+        let extend = matches!(mods, Shift | ControlShift);
+        let ctrl = matches!(mods, Control | ControlShift);
+        let page = self.dim.get().height.max(1);
+        match key {
+            NavKey::Up => return self.vertical(true, 1, extend),
+            NavKey::Down => return self.vertical(false, 1, extend),
+            NavKey::PageUp => return self.vertical(true, page, extend),
+            NavKey::PageDown => return self.vertical(false, page, extend),
+            NavKey::Home if !ctrl => self.move_row_edge(false, extend),
+            NavKey::End if !ctrl => self.move_row_edge(true, extend),
+            _ => {
+                let mut buffer = self.buffer.borrow_mut();
+                let cursor = buffer.cursor();
+                let selection = buffer.selection().filter(|_| !extend);
+                match key {
+                    NavKey::Left => {
+                        let offset = match (selection, ctrl) {
+                            (Some(sel), _) => sel.start,
+                            (None, true) => buffer.prev_word(cursor),
+                            (None, false) => buffer.prev_grapheme(cursor),
+                        };
+                        buffer.move_to(offset, extend);
+                    }
+                    NavKey::Right => {
+                        let offset = match (selection, ctrl) {
+                            (Some(sel), _) => sel.end,
+                            (None, true) => buffer.next_word(cursor),
+                            (None, false) => buffer.next_grapheme(cursor),
+                        };
+                        buffer.move_to(offset, extend);
+                    }
+                    NavKey::Home => buffer.move_to(0, extend),
+                    NavKey::End => {
+                        let end = buffer.text().len();
+                        buffer.move_to(end, extend);
+                    }
+                    NavKey::Backspace if ctrl => {
+                        buffer.delete_word_back();
+                    }
+                    NavKey::Backspace => {
+                        buffer.delete_back();
+                    }
+                    NavKey::Delete if ctrl => {
+                        buffer.delete_word_forward();
+                    }
+                    NavKey::Delete => {
+                        buffer.delete_forward();
+                    }
+                    NavKey::Enter => buffer.insert("\n"),
+                    _ => return None,
+                }
+            }
+        }
+        self.goal_col.set(None);
+        Some(Action::Redraw())
+    }
+
+    /// Move the cursor vertically, returning a `Redraw` action
+    fn vertical(&self, up: bool, count: u16, extend: bool) -> Option<Action> {
+        self.move_rows(up, count, extend);
+        Some(Action::Redraw())
+    }
+
+    /// Handle a character key
+    fn char_key(&self, ch: char, mods: ModKeys) -> Option<Action> {
+        let mut buffer = self.buffer.borrow_mut();
+        match (ch, mods) {
+            ('a', ModKeys::Control) => buffer.select_all(),
+            ('w', ModKeys::Control) => {
+                buffer.delete_word_back();
+            }
+            ('z', ModKeys::Control) => {
+                buffer.undo();
+            }
+            ('y', ModKeys::Control)
+            | ('z', ModKeys::ControlShift)
+            | ('Z', ModKeys::ControlShift) => {
+                buffer.redo();
+            }
+            (_, ModKeys::Empty) | (_, ModKeys::Shift) if !ch.is_control() => {
+                let mut buf = [0; 4];
+                buffer.insert(ch.encode_utf8(&mut buf));
+            }
+            _ => return None,
+        }
+        self.goal_col.set(None);
+        Some(Action::Redraw())
+    }
+}
+
+impl Widget for TextArea {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        match self.state.get() {
+            State::Disabled => StyleGroup::Disabled,
+            State::Enabled => StyleGroup::Enabled,
+            State::Focused => StyleGroup::Focused,
+        }
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        if self.wrap {
+            LengthBound::new(1..)
+        } else {
+            let buffer = self.buffer.borrow();
+            let w = buffer.text().split('\n').map(str_width).max();
+            // Leave room for the cursor at the end of the longest line
+            LengthBound::new(w.unwrap_or(0).saturating_add(1)..)
+        }
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, width: u16) -> LengthBound {
+        let rows = self.rows(width).len() as u16;
+        LengthBound::new(rows..)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let width = cells.width();
+        let height = cells.height();
+        self.dim.set(Dim::new(width, height));
+        let rows = self.rows(width);
+        let style = cells.style(self.style_group());
+        let selected = cells.style(StyleGroup::Interacted);
+        let buffer = self.buffer.borrow();
+        let selection = buffer.selection().unwrap_or_default();
+        let visible = rows
+            .iter()
+            .enumerate()
+            .skip(usize::from(offset.row))
+            .take(usize::from(height));
+        for (row, range) in visible {
+            let row = row as u16 - offset.row;
+            let line = &buffer.text()[range.clone()];
+            cells.move_to(0, row)?;
+            for (i, g, cut) in visible_graphemes(line, offset.col, width, |g| g)
+            {
+                if selection.contains(&(range.start + i)) {
+                    cells.set_style(selected)?;
+                } else {
+                    cells.set_style(style)?;
+                }
+                cells.print_str_cut(g, cut)?;
+            }
+        }
+        cells.set_style(style)?;
+        if self.state.get() == State::Focused {
+            let pos = self.offset_pos(&rows, buffer.cursor());
+            if pos.col >= offset.col && pos.row >= offset.row {
+                let pos = pos - offset;
+                cells.show_cursor(pos.col, pos.row);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        let state = match (fev, self.state.get()) {
+            (_, State::Disabled) => return None,
+            (FocusEvent::Offer, _) => State::Focused,
+            (FocusEvent::Take, _) => State::Enabled,
+            _ => return None,
+        };
+        if state != self.state.get() {
+            self.state.set(state);
+            Some(Action::Redraw())
+        } else {
+            None
+        }
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        if self.state.get() == State::Disabled {
+            return None;
+        }
+        let extend = match (mev, mods) {
+            (MouseEvent::ButtonDown(MouseButton::Left), ModKeys::Shift) => true,
+            (MouseEvent::ButtonDown(MouseButton::Left), _) => false,
+            (MouseEvent::Drag(Some(MouseButton::Left)), _) => true,
+            _ => return None,
+        };
+        let rows = self.rows(self.dim.get().width);
+        let offset = self.pos_offset(&rows, pos);
+        self.buffer.borrow_mut().move_to(offset, extend);
+        self.goal_col.set(None);
+        Some(Action::Redraw())
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if self.state.get() != State::Focused {
+            return None;
+        }
+        match key {
+            KeyPress::Navigation(nav) => self.nav_key(nav, mods),
+            KeyPress::Character(ch) => self.char_key(ch, mods),
+            _ => None,
+        }
+    }
+
+    /// Get the text cursor position
+    fn cursor(&self) -> Option<Pos> {
+        let width = self.dim.get().width;
+        if self.state.get() == State::Focused && width > 0 {
+            let rows = self.rows(width);
+            Some(self.offset_pos(&rows, self.buffer.borrow().cursor()))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn area(text: &str, width: u16, height: u16) -> TextArea {
+        let area = TextArea::new(text);
+        area.dim.set(Dim::new(width, height));
+        area.focus(FocusEvent::Offer);
+        area
+    }
+
+    fn key(area: &TextArea, key: NavKey, mods: ModKeys) {
+        area.key_event(KeyPress::Navigation(key), mods);
+    }
+
+    fn ctrl(area: &TextArea, ch: char) {
+        area.key_event(KeyPress::Character(ch), ModKeys::Control);
+    }
+
+    #[test]
+    fn wrap() {
+        assert_eq!(layout_rows("abc\n\nde", None), [0..3, 4..4, 5..7]);
+        assert_eq!(layout_rows("one two three", Some(8)), [0..8, 8..13]);
+        assert_eq!(layout_rows("abcdefgh", Some(3)), [0..3, 3..6, 6..8]);
+        assert_eq!(layout_rows("ab   cd", Some(3)), [0..5, 5..7]);
+        assert_eq!(layout_rows("🦀🦀🦀", Some(5)), [0..8, 8..12]);
+    }
+
+    #[test]
+    fn vertical() {
+        let ta = area("one two three\nfour", 8, 2);
+        assert_eq!(ta.cursor(), Some(Pos::new(4, 2)));
+        key(&ta, NavKey::Up, ModKeys::Empty);
+        assert_eq!(ta.cursor(), Some(Pos::new(4, 1)));
+        key(&ta, NavKey::Up, ModKeys::Empty);
+        assert_eq!(ta.cursor(), Some(Pos::new(4, 0)));
+        key(&ta, NavKey::End, ModKeys::Empty);
+        assert_eq!(ta.cursor(), Some(Pos::new(7, 0)));
+        key(&ta, NavKey::PageDown, ModKeys::Shift);
+        assert_eq!(ta.cursor(), Some(Pos::new(4, 2)));
+        assert_eq!(ta.selected_text().as_deref(), Some(" three\nfour"));
+        key(&ta, NavKey::Home, ModKeys::Control);
+        assert_eq!(ta.cursor(), Some(Pos::new(0, 0)));
+    }
+
+    #[test]
+    fn undo() {
+        let ta = area("", 20, 5);
+        for ch in "hello".chars() {
+            ta.key_event(KeyPress::Character(ch), ModKeys::Empty);
+        }
+        key(&ta, NavKey::Enter, ModKeys::Empty);
+        ta.key_event(KeyPress::Character('x'), ModKeys::Empty);
+        assert_eq!(ta.text(), "hello\nx");
+        ctrl(&ta, 'z');
+        assert_eq!(ta.text(), "hello\n");
+        ctrl(&ta, 'z');
+        assert_eq!(ta.text(), "hello");
+        ctrl(&ta, 'y');
+        assert_eq!(ta.text(), "hello\n");
+        assert_eq!(ta.cursor(), Some(Pos::new(0, 1)));
+    }
+
+    #[test]
+    fn mouse() {
+        let ta = area("first\nsecond", 20, 5);
+        let down = MouseEvent::ButtonDown(MouseButton::Left);
+        let drag = MouseEvent::Drag(Some(MouseButton::Left));
+        let dim = Dim::new(20, 5);
+        ta.mouse_event(down, ModKeys::Empty, dim, Pos::new(2, 0));
+        ta.mouse_event(drag, ModKeys::Empty, dim, Pos::new(3, 1));
+        assert_eq!(ta.selected_text().as_deref(), Some("rst\nsec"));
+        ta.mouse_event(down, ModKeys::Empty, dim, Pos::new(10, 3));
+        assert_eq!(ta.cursor(), Some(Pos::new(6, 1)));
+    }
+}
@@ -0,0 +1,741 @@
+// textinput.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent, NavKey,
+};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{grapheme_width, StyleGroup, Theme};
+use crate::widget::{visible_graphemes, EditBuffer};
+use crate::{Result, Widget};
+use std::cell::{Cell, RefCell};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Text input state
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    /// Input disabled
+    Disabled,
+    /// Input enabled
+    Enabled,
+    /// Input focused
+    Focused,
+}
+
+/// Pattern slot
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Slot {
+    /// ASCII digit (`9`)
+    Digit,
+    /// Letter (`A`)
+    Letter,
+    /// Letter or digit (`*`)
+    Alphanumeric,
+    /// Literal character
+    Literal(char),
+}
+
+/// Text validator function
+type Validator = Box<dyn Fn(&str) -> bool>;
+
+/// Single-line text input widget
+///
+/// When focused, the text cursor is shown and key presses edit the text:
+///
+/// Key                        | Edit
+/// ---------------------------|-----
+/// `Left` / `Right`           | Move by character
+/// `Ctrl` + `Left` / `Right`  | Move by word
+/// `Home` / `End`             | Move to start / end
+/// `Shift` + movement         | Extend selection
+/// `Ctrl` + `A`               | Select all
+/// `Backspace` / `Delete`     | Delete character (or selection)
+/// `Ctrl` + `Backspace` / `W` | Delete previous word
+/// `Ctrl` + `Delete`          | Delete next word
+/// `Enter`                    | Submit (`Action::Submit`)
+///
+/// Text which does not fit is scrolled horizontally to keep the cursor
+/// visible.
+///
+/// For passwords, characters can be masked:
+///
+/// ```rust
+/// use semtext::widget::TextInput;
+///
+/// let password = TextInput::new("").with_mask('•').with_reveal_toggle(true);
+/// ```
+///
+/// Input can also be restricted with a [pattern] or [validator], which are
+/// checked as each character is typed.
+///
+/// [pattern]: struct.TextInput.html#method.with_pattern
+/// [validator]: struct.TextInput.html#method.with_validator
+pub struct TextInput {
+    /// Edit buffer
+    buffer: RefCell<EditBuffer>,
+    /// Placeholder text, shown when empty
+    placeholder: String,
+    /// Input state
+    state: Cell<State>,
+    /// Horizontal scroll offset (columns)
+    scroll: Cell<u16>,
+    /// Mask glyph, for hidden text
+    mask: Option<String>,
+    /// Allow revealing masked text with `Ctrl` + `R`
+    reveal_toggle: bool,
+    /// Masked text revealed
+    revealed: Cell<bool>,
+    /// Pattern slots
+    pattern: Vec<Slot>,
+    /// Text validator
+    validator: Option<Validator>,
+}
+
+impl Slot {
+    /// Check if the slot accepts a character
+    fn accepts(self, ch: char) -> bool {
+        match self {
+            Slot::Digit => ch.is_ascii_digit(),
+            Slot::Letter => ch.is_alphabetic(),
+            Slot::Alphanumeric => ch.is_alphanumeric(),
+            Slot::Literal(c) => c == ch,
+        }
+    }
+
+    /// Get the placeholder character for the slot
+    fn placeholder(self) -> char {
+        match self {
+            Slot::Literal(c) => c,
+            _ => '_',
+        }
+    }
+}
+
+/// Parse a pattern into slots
+fn parse_pattern(pattern: &str) -> Vec<Slot> {
+    let mut slots = vec![];
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        let slot = match ch {
+            '9' => Slot::Digit,
+            'A' => Slot::Letter,
+            '*' => Slot::Alphanumeric,
+            '\\' => match chars.next() {
+                Some(c) => Slot::Literal(c),
+                None => break,
+            },
+            _ => Slot::Literal(ch),
+        };
+        slots.push(slot);
+    }
+    slots
+}
+
+impl TextInput {
+    /// Create a new text input widget
+    pub fn new(text: &str) -> Self {
+        let buffer = RefCell::new(EditBuffer::new(&filter_text(text)));
+        let placeholder = String::new();
+        let state = Cell::new(State::Enabled);
+        let scroll = Cell::new(0);
+        let mask = None;
+        let reveal_toggle = false;
+        let revealed = Cell::new(false);
+        let pattern = vec![];
+        let validator = None;
+        Self {
+            buffer,
+            placeholder,
+            state,
+            scroll,
+            mask,
+            reveal_toggle,
+            revealed,
+            pattern,
+            validator,
+        }
+    }
+
+    /// Set the placeholder text, shown when the input is empty
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    /// Mask each character with a glyph, such as `*` or `•`
+    ///
+    /// Word movement and deletion treat masked text as a single word.
+    pub fn with_mask(mut self, mask: char) -> Self {
+        self.mask = Some(mask.to_string());
+        self
+    }
+
+    /// Allow toggling masked text with `Ctrl` + `R`
+    pub fn with_reveal_toggle(mut self, enable: bool) -> Self {
+        self.reveal_toggle = enable;
+        self
+    }
+
+    /// Restrict input to a pattern
+    ///
+    /// Character | Matches
+    /// ----------|--------
+    /// `9`       | ASCII digit
+    /// `A`       | Letter
+    /// `*`       | Letter or digit
+    /// `\`       | Next character, literally
+    ///
+    /// Other characters are literals, which are inserted automatically while
+    /// typing.  Typed characters which do not match are rejected, but
+    /// deletions are always allowed.  Text can only be submitted when the
+    /// pattern is complete.  If no placeholder is set, the pattern is shown
+    /// with `_` for each slot.
+    ///
+    /// ```rust
+    /// use semtext::widget::TextInput;
+    ///
+    /// let date = TextInput::new("").with_pattern("9999-99-99");
+    /// let phone = TextInput::new("").with_pattern("(999) 999-9999");
+    /// ```
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.pattern = parse_pattern(pattern);
+        if self.placeholder.is_empty() {
+            self.placeholder =
+                self.pattern.iter().map(|s| s.placeholder()).collect();
+        }
+        self
+    }
+
+    /// Restrict input with a validator function
+    ///
+    /// The function is called with the text resulting from each insertion,
+    /// which is rejected unless it returns `true`.  It must accept partial
+    /// input, including an empty string.  Deletions are never rejected, so
+    /// invalid text (such as the initial text) can always be corrected.
+    ///
+    /// ```rust
+    /// use semtext::widget::TextInput;
+    ///
+    /// // IPv4 address
+    /// let ip = TextInput::new("").with_validator(|text| {
+    ///     let octets: Vec<&str> = text.split('.').collect();
+    ///     octets.len() <= 4
+    ///         && octets.iter().all(|o| {
+    ///             o.len() <= 3
+    ///                 && o.chars().all(|c| c.is_ascii_digit())
+    ///                 && o.parse::<u16>().map_or(true, |v| v <= 255)
+    ///         })
+    /// });
+    /// ```
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Get the text
+    pub fn text(&self) -> String {
+        self.buffer.borrow().text().to_string()
+    }
+
+    /// Set the text
+    ///
+    /// The cursor is moved to the end, and the selection cleared.
+    pub fn set_text(&self, text: &str) {
+        self.buffer.borrow_mut().set_text(&filter_text(text));
+    }
+
+    /// Get the selected text
+    pub fn selected_text(&self) -> Option<String> {
+        self.buffer.borrow().selected_text().map(str::to_string)
+    }
+
+    /// Disable the input
+    pub fn disable(&self) {
+        self.state.set(State::Disabled);
+    }
+
+    /// Enable the input
+    pub fn enable(&self) {
+        if self.state.get() == State::Disabled {
+            self.state.set(State::Enabled);
+        }
+    }
+
+    /// Reveal or mask text (if a mask is set)
+    pub fn set_revealed(&self, revealed: bool) {
+        self.revealed.set(revealed);
+    }
+
+    /// Check if masked text is revealed
+    pub fn is_revealed(&self) -> bool {
+        self.revealed.get()
+    }
+
+    /// Check if the text is masked
+    fn is_masked(&self) -> bool {
+        self.mask.is_some() && !self.revealed.get()
+    }
+
+    /// Get the text shown for a grapheme cluster
+    fn shown<'a>(&'a self, grapheme: &'a str) -> &'a str {
+        match &self.mask {
+            Some(mask) if !self.revealed.get() => mask,
+            _ => grapheme,
+        }
+    }
+
+    /// Get the width of a str in text cells, as shown
+    fn str_width(&self, st: &str) -> u16 {
+        st.graphemes(true)
+            .map(|g| grapheme_width(self.shown(g)))
+            .sum::<usize>() as u16
+    }
+
+    /// Check if text is valid for the pattern and validator
+    fn is_valid(&self, text: &str) -> bool {
+        let pattern = self.pattern.is_empty()
+            || (text.chars().count() <= self.pattern.len()
+                && text.chars().zip(&self.pattern).all(|(c, s)| s.accepts(c)));
+        pattern && self.validator.as_ref().map_or(true, |v| v(text))
+    }
+
+    /// Check if the text is complete for the pattern
+    fn is_complete(&self, text: &str) -> bool {
+        self.pattern.is_empty() || text.chars().count() == self.pattern.len()
+    }
+
+    /// Apply an edit to the buffer, unless it changes the text to be invalid
+    fn edit<F: FnOnce(&mut EditBuffer)>(&self, f: F) {
+        if self.pattern.is_empty() && self.validator.is_none() {
+            f(&mut self.buffer.borrow_mut());
+        } else {
+            let mut buffer = self.buffer.borrow().clone();
+            f(&mut buffer);
+            let changed = buffer.text() != self.buffer.borrow().text();
+            if !changed || self.is_valid(buffer.text()) {
+                *self.buffer.borrow_mut() = buffer;
+            }
+        }
+    }
+
+    /// Insert a character, with pattern literals before it
+    fn insert_char(&self, buffer: &mut EditBuffer, ch: char) {
+        let mut buf = [0; 4];
+        if !self.pattern.is_empty() {
+            buffer.delete_selection();
+            let pos = buffer.text()[..buffer.cursor()].chars().count();
+            for slot in &self.pattern[pos.min(self.pattern.len())..] {
+                match slot {
+                    Slot::Literal(lit) if *lit != ch => {
+                        buffer.insert(lit.encode_utf8(&mut buf));
+                    }
+                    _ => break,
+                }
+            }
+        }
+        buffer.insert(ch.encode_utf8(&mut buf));
+    }
+
+    /// Get the buffer offset at a column
+    fn offset_at(&self, col: u16) -> usize {
+        let buffer = self.buffer.borrow();
+        let col = col.saturating_add(self.scroll.get());
+        let mut width = 0;
+        for (i, g) in buffer.text().grapheme_indices(true) {
+            let w = grapheme_width(self.shown(g)) as u16;
+            if width + w > col {
+                // Round to the nearest grapheme boundary
+                return if col - width > w / 2 { i + g.len() } else { i };
+            }
+            width += w;
+        }
+        buffer.text().len()
+    }
+
+    /// Update the scroll offset to keep the cursor visible
+    fn update_scroll(&self, width: u16) -> u16 {
+        let buffer = self.buffer.borrow();
+        let col = self.str_width(&buffer.text()[..buffer.cursor()]);
+        let mut scroll = self.scroll.get();
+        if col < scroll {
+            scroll = col;
+        } else if col >= scroll + width {
+            scroll = col + 1 - width;
+        }
+        // Don't leave empty space when text is deleted
+        let total = self.str_width(buffer.text()) + 1;
+        scroll = scroll.min(total.saturating_sub(width));
+        self.scroll.set(scroll);
+        col - scroll
+    }
+
+    /// Handle a navigation key
+    fn nav_key(&self, key: NavKey, mods: ModKeys) -> Option<Action> {
+        use ModKeys::*;
+        let extend = matches!(mods, Shift | ControlShift);
+        let word = matches!(mods, Control | ControlShift);
+        let masked = self.is_masked();
+        // Masked text is treated as a single word
+        let prev_word = |buffer: &EditBuffer, cursor| {
+            if masked {
+                0
+            } else {
+                buffer.prev_word(cursor)
+            }
+        };
+        let next_word = |buffer: &EditBuffer, cursor| {
+            if masked {
+                buffer.text().len()
+            } else {
+                buffer.next_word(cursor)
+            }
+        };
+        // Delete the selection, or up to an offset
+        let delete_to = |buffer: &mut EditBuffer, offset| {
+            if !buffer.delete_selection() {
+                buffer.move_to(offset, true);
+                buffer.delete_selection();
+            }
+        };
+        match key {
+            NavKey::Enter => {
+                let text = self.text();
+                return if self.is_complete(&text) {
+                    Some(Action::Submit(text))
+                } else {
+                    None
+                };
+            }
+            NavKey::Left
+            | NavKey::Right
+            | NavKey::Home
+            | NavKey::End
+            | NavKey::Backspace
+            | NavKey::Delete => (),
+            _ => return None,
+        }
+        // Moves and deletions are never rejected by validation
+        {
+            let buffer = &mut *self.buffer.borrow_mut();
+            let cursor = buffer.cursor();
+            let selection = buffer.selection().filter(|_| !extend);
+            match key {
+                NavKey::Left => {
+                    let offset = match (selection, word) {
+                        (Some(sel), _) => sel.start,
+                        (None, true) => prev_word(buffer, cursor),
+                        (None, false) => buffer.prev_grapheme(cursor),
+                    };
+                    buffer.move_to(offset, extend);
+                }
+                NavKey::Right => {
+                    let offset = match (selection, word) {
+                        (Some(sel), _) => sel.end,
+                        (None, true) => next_word(buffer, cursor),
+                        (None, false) => buffer.next_grapheme(cursor),
+                    };
+                    buffer.move_to(offset, extend);
+                }
+                NavKey::Home => buffer.move_to(0, extend),
+                NavKey::End => {
+                    let end = buffer.text().len();
+                    buffer.move_to(end, extend);
+                }
+                NavKey::Backspace if word => {
+                    delete_to(buffer, prev_word(buffer, cursor));
+                }
+                NavKey::Backspace => {
+                    buffer.delete_back();
+                }
+                NavKey::Delete if word => {
+                    delete_to(buffer, next_word(buffer, cursor));
+                }
+                NavKey::Delete => {
+                    buffer.delete_forward();
+                }
+                _ => (),
+            }
+        }
+        Some(Action::Redraw())
+    }
+
+    /// Handle a character key
+    fn char_key(&self, ch: char, mods: ModKeys) -> Option<Action> {
+        match (ch, mods) {
+            ('a', ModKeys::Control) => {
+                self.buffer.borrow_mut().select_all();
+            }
+            ('w', ModKeys::Control) => {
+                let word = KeyPress::Navigation(NavKey::Backspace);
+                return self.key_event(word, ModKeys::Control);
+            }
+            ('r', ModKeys::Control)
+                if self.reveal_toggle && self.mask.is_some() =>
+            {
+                self.revealed.set(!self.revealed.get());
+            }
+            (_, ModKeys::Empty) | (_, ModKeys::Shift) if !ch.is_control() => {
+                self.edit(|buffer| self.insert_char(buffer, ch));
+            }
+            _ => return None,
+        }
+        Some(Action::Redraw())
+    }
+}
+
+/// Filter control characters (including newlines) from text
+fn filter_text(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+impl Widget for TextInput {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        match self.state.get() {
+            State::Disabled => StyleGroup::Disabled,
+            State::Enabled => StyleGroup::Enabled,
+            State::Focused => StyleGroup::Focused,
+        }
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        // Leave room for the cursor after the placeholder
+        let w = (self.placeholder.width() as u16).saturating_add(1);
+        let w = w.max(self.pattern.len() as u16 + 1);
+        LengthBound::new(w..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        LengthBound::new(1..=1)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let width = cells.width();
+        if offset.row > 0 || width == 0 {
+            return Ok(());
+        }
+        let cursor_col = self.update_scroll(width);
+        let style = cells.style(self.style_group());
+        cells.move_to(0, 0)?;
+        let buffer = self.buffer.borrow();
+        if buffer.text().is_empty() {
+            cells.set_style(cells.style(StyleGroup::Muted))?;
+            cells.print_str(&self.placeholder)?;
+        } else {
+            let scroll = self.scroll.get();
+            let selection = buffer.selection().unwrap_or_default();
+            let selected = cells.style(StyleGroup::Interacted);
+            let text = buffer.text();
+            for (i, g, cut) in
+                visible_graphemes(text, scroll, width, |g| self.shown(g))
+            {
+                if selection.contains(&i) {
+                    cells.set_style(selected)?;
+                } else {
+                    cells.set_style(style)?;
+                }
+                cells.print_str_cut(g, cut)?;
+            }
+        }
+        cells.set_style(style)?;
+        if self.state.get() == State::Focused {
+            cells.show_cursor(cursor_col, 0);
+        }
+        Ok(())
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        let state = match (fev, self.state.get()) {
+            (_, State::Disabled) => return None,
+            (FocusEvent::Offer, _) => State::Focused,
+            (FocusEvent::Take, _) => State::Enabled,
+            _ => return None,
+        };
+        if state != self.state.get() {
+            self.state.set(state);
+            Some(Action::Redraw())
+        } else {
+            None
+        }
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        if self.state.get() == State::Disabled || pos.row > 0 {
+            return None;
+        }
+        let extend = match (mev, mods) {
+            (MouseEvent::ButtonDown(MouseButton::Left), ModKeys::Shift) => true,
+            (MouseEvent::ButtonDown(MouseButton::Left), _) => false,
+            (MouseEvent::Drag(Some(MouseButton::Left)), _) => true,
+            _ => return None,
+        };
+        let offset = self.offset_at(pos.col);
+        self.buffer.borrow_mut().move_to(offset, extend);
+        Some(Action::Redraw())
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if self.state.get() != State::Focused {
+            return None;
+        }
+        match key {
+            KeyPress::Navigation(nav) => self.nav_key(nav, mods),
+            KeyPress::Character(ch) => self.char_key(ch, mods),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input(text: &str) -> TextInput {
+        let input = TextInput::new(text);
+        input.focus(FocusEvent::Offer);
+        input
+    }
+
+    fn key(input: &TextInput, key: NavKey, mods: ModKeys) {
+        input.key_event(KeyPress::Navigation(key), mods);
+    }
+
+    fn typ(input: &TextInput, text: &str) {
+        for ch in text.chars() {
+            input.key_event(KeyPress::Character(ch), ModKeys::Empty);
+        }
+    }
+
+    #[test]
+    fn editing() {
+        let ti = input("hello");
+        typ(&ti, " world");
+        assert_eq!(ti.text(), "hello world");
+        key(&ti, NavKey::Left, ModKeys::Control);
+        key(&ti, NavKey::Backspace, ModKeys::Empty);
+        typ(&ti, ", ");
+        assert_eq!(ti.text(), "hello, world");
+        key(&ti, NavKey::End, ModKeys::Shift);
+        assert_eq!(ti.selected_text().as_deref(), Some("world"));
+        typ(&ti, "there");
+        assert_eq!(ti.text(), "hello, there");
+        key(&ti, NavKey::Backspace, ModKeys::Control);
+        assert_eq!(ti.text(), "hello, ");
+        assert_eq!(
+            ti.key_event(KeyPress::Navigation(NavKey::Enter), ModKeys::Empty),
+            Some(Action::Submit("hello, ".to_string()))
+        );
+    }
+
+    #[test]
+    fn unfocused() {
+        let ti = TextInput::new("abc");
+        typ(&ti, "d");
+        assert_eq!(ti.text(), "abc");
+        assert_eq!(
+            ti.key_event(KeyPress::Navigation(NavKey::Esc), ModKeys::Empty),
+            None
+        );
+    }
+
+    #[test]
+    fn scroll() {
+        let ti = input("0123456789");
+        assert_eq!(ti.update_scroll(5), 4);
+        assert_eq!(ti.scroll.get(), 6);
+        key(&ti, NavKey::Home, ModKeys::Empty);
+        assert_eq!(ti.update_scroll(5), 0);
+        assert_eq!(ti.scroll.get(), 0);
+        ti.mouse_event(
+            MouseEvent::ButtonDown(MouseButton::Left),
+            ModKeys::Empty,
+            Dim::new(5, 1),
+            Pos::new(3, 0),
+        );
+        assert_eq!(ti.buffer.borrow().cursor(), 3);
+    }
+
+    #[test]
+    fn mask() {
+        let ti = TextInput::new("").with_mask('*').with_reveal_toggle(true);
+        ti.focus(FocusEvent::Offer);
+        typ(&ti, "pass word");
+        assert_eq!(ti.update_scroll(20), 9);
+        key(&ti, NavKey::Left, ModKeys::Control);
+        assert_eq!(ti.buffer.borrow().cursor(), 0);
+        ti.key_event(KeyPress::Character('r'), ModKeys::Control);
+        assert!(ti.is_revealed());
+        assert_eq!(ti.update_scroll(20), 0);
+        key(&ti, NavKey::Right, ModKeys::Control);
+        assert_eq!(ti.update_scroll(20), 4);
+    }
+
+    #[test]
+    fn pattern() {
+        let ti = TextInput::new("").with_pattern("(999) 999-9999");
+        ti.focus(FocusEvent::Offer);
+        assert_eq!(ti.placeholder, "(___) ___-____");
+        typ(&ti, "555x1234");
+        assert_eq!(ti.text(), "(555) 123-4");
+        let enter = KeyPress::Navigation(NavKey::Enter);
+        assert_eq!(ti.key_event(enter, ModKeys::Empty), None);
+        typ(&ti, "5678");
+        assert_eq!(ti.text(), "(555) 123-4567");
+        assert_eq!(
+            ti.key_event(enter, ModKeys::Empty),
+            Some(Action::Submit("(555) 123-4567".to_string()))
+        );
+        // Deletions are not rejected
+        key(&ti, NavKey::Home, ModKeys::Empty);
+        key(&ti, NavKey::Delete, ModKeys::Empty);
+        assert_eq!(ti.text(), "555) 123-4567");
+        assert_eq!(ti.key_event(enter, ModKeys::Empty), None);
+    }
+
+    #[test]
+    fn validator() {
+        let ti = TextInput::new("")
+            .with_validator(|t| t.parse::<u8>().is_ok() || t.is_empty());
+        ti.focus(FocusEvent::Offer);
+        typ(&ti, "25x6");
+        assert_eq!(ti.text(), "25");
+        typ(&ti, "5");
+        assert_eq!(ti.text(), "255");
+        typ(&ti, "0");
+        assert_eq!(ti.text(), "255");
+    }
+
+    #[test]
+    fn invalid_text() {
+        let ti = input("abc").with_validator(|t| t.parse::<u8>().is_ok());
+        key(&ti, NavKey::Left, ModKeys::Empty);
+        key(&ti, NavKey::Backspace, ModKeys::Empty);
+        assert_eq!(ti.text(), "ac");
+        typ(&ti, "1");
+        assert_eq!(ti.text(), "ac");
+        key(&ti, NavKey::Home, ModKeys::Empty);
+        key(&ti, NavKey::Delete, ModKeys::Empty);
+        key(&ti, NavKey::Delete, ModKeys::Empty);
+        typ(&ti, "1");
+        assert_eq!(ti.text(), "1");
+        key(&ti, NavKey::Backspace, ModKeys::Empty);
+        assert_eq!(ti.text(), "");
+    }
+}
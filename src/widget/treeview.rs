@@ -0,0 +1,573 @@
+// treeview.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent, NavKey,
+};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{Outline, StyleGroup, Theme};
+use crate::widget::{
+    fill_row, list_style_group, nav_index, ButtonState, RowScroll,
+};
+use crate::{Result, Widget};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Source of tree nodes
+///
+/// Children are loaded lazily, when a node is first expanded.
+pub trait TreeSource {
+    /// Node type
+    type Node;
+
+    /// Get the root nodes
+    fn roots(&self) -> Vec<Self::Node>;
+
+    /// Get the label of a node
+    fn label(&self, node: &Self::Node) -> Cow<'_, str>;
+
+    /// Check if a node has (or may have) children
+    fn has_children(&self, node: &Self::Node) -> bool;
+
+    /// Load the children of a node
+    fn children(&self, node: &Self::Node) -> Vec<Self::Node>;
+}
+
+/// Loaded tree node
+struct TreeNode<N> {
+    /// Source node
+    node: N,
+    /// Node may have children
+    expandable: bool,
+    /// Child nodes (once loaded)
+    children: Option<Vec<TreeNode<N>>>,
+    /// Node expanded
+    expanded: bool,
+}
+
+/// Visible tree row
+#[derive(Debug, PartialEq)]
+struct Row {
+    /// Path of child indices to node
+    path: Vec<usize>,
+    /// Guide line prefix
+    guides: String,
+}
+
+/// Tree view widget
+///
+/// Nodes are drawn with guide lines from an [Outline].  While focused, keys
+/// navigate the tree:
+///
+/// Key                   | Action
+/// ----------------------|-------
+/// `Up` / `Down`         | Select previous / next row
+/// `PageUp` / `PageDown` | Move by visible rows
+/// `Home` / `End`        | Select first / last row
+/// `Right`               | Expand node, or select first child
+/// `Left`                | Collapse node, or select parent
+/// `Enter` / `Space`     | Toggle node expansion
+///
+/// Clicking a node selects it; clicking its expander toggles expansion.
+/// Selecting a node produces `Action::NodeSelected`, with the path of child
+/// indices from the roots.
+///
+/// [Outline]: ../text/enum.Outline.html
+pub struct TreeView<S: TreeSource> {
+    /// Node source
+    source: S,
+    /// Guide line outline
+    outline: Outline,
+    /// Button state
+    button: ButtonState,
+    /// Root nodes
+    roots: RefCell<Vec<TreeNode<S::Node>>>,
+    /// Visible rows
+    rows: RefCell<Vec<Row>>,
+    /// Selected row
+    selected: Cell<Option<usize>>,
+    /// Row scrolling state
+    scroll: RowScroll,
+}
+
+impl<N> TreeNode<N> {
+    /// Create a tree node
+    fn new<S: TreeSource<Node = N>>(source: &S, node: N) -> Self {
+        let expandable = source.has_children(&node);
+        Self {
+            node,
+            expandable,
+            children: None,
+            expanded: false,
+        }
+    }
+
+    /// Get a descendant node by path
+    fn find<'a>(nodes: &'a mut [Self], path: &[usize]) -> Option<&'a mut Self> {
+        let (first, rest) = path.split_first()?;
+        let node = nodes.get_mut(*first)?;
+        if rest.is_empty() {
+            Some(node)
+        } else {
+            Self::find(node.children.as_deref_mut()?, rest)
+        }
+    }
+}
+
+/// Add visible rows for a list of sibling nodes
+///
+/// * `nodes`: Sibling nodes
+/// * `path`: Path to parent node
+/// * `guides`: Guide lines for ancestors
+/// * `outline`: Guide line outline
+/// * `rows`: Visible rows
+fn flatten<N>(
+    nodes: &[TreeNode<N>],
+    path: &mut Vec<usize>,
+    guides: &str,
+    outline: Outline,
+    rows: &mut Vec<Row>,
+) {
+    let depth = path.len();
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i + 1 == nodes.len();
+        path.push(i);
+        let mut row_guides = guides.to_string();
+        let mut child_guides = guides.to_string();
+        if depth > 0 {
+            let branch = if last {
+                outline.bottom_left(outline)
+            } else {
+                outline.left_tee()
+            };
+            row_guides.push(branch);
+            row_guides.push(outline.top());
+            child_guides.push(if last { ' ' } else { outline.left() });
+            child_guides.push(' ');
+        }
+        rows.push(Row {
+            path: path.clone(),
+            guides: row_guides,
+        });
+        if let (true, Some(children)) = (node.expanded, &node.children) {
+            flatten(children, path, &child_guides, outline, rows);
+        }
+        path.pop();
+    }
+}
+
+impl<S: TreeSource> TreeView<S> {
+    /// Create a new tree view widget
+    pub fn new(source: S) -> Self {
+        let outline = Outline::default();
+        let button = ButtonState::new();
+        let roots = source
+            .roots()
+            .into_iter()
+            .map(|node| TreeNode::new(&source, node))
+            .collect();
+        let roots = RefCell::new(roots);
+        let rows = RefCell::new(vec![]);
+        let selected = Cell::new(None);
+        let scroll = RowScroll::default();
+        let tree = Self {
+            source,
+            outline,
+            button,
+            roots,
+            rows,
+            selected,
+            scroll,
+        };
+        tree.rebuild();
+        tree
+    }
+
+    /// Set the guide line outline
+    pub fn with_outline(mut self, outline: Outline) -> Self {
+        self.outline = outline;
+        self.rebuild();
+        self
+    }
+
+    /// Get the node source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get the path of the selected node
+    pub fn selected(&self) -> Option<Vec<usize>> {
+        let rows = self.rows.borrow();
+        self.selected.get().map(|r| rows[r].path.clone())
+    }
+
+    /// Select a node by path, expanding its ancestors
+    pub fn set_selected(&self, path: &[usize]) {
+        for len in 1..path.len() {
+            self.set_expanded(&path[..len], true);
+        }
+        self.selected.set(self.row_of(path));
+    }
+
+    /// Check if a node is expanded
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        let mut roots = self.roots.borrow_mut();
+        TreeNode::find(&mut roots, path).map_or(false, |n| n.expanded)
+    }
+
+    /// Expand or collapse a node
+    ///
+    /// Children are loaded from the source when first expanded.  Returns
+    /// `true` if the node changed.
+    pub fn set_expanded(&self, path: &[usize], expanded: bool) -> bool {
+        let selected = self.selected();
+        {
+            let mut roots = self.roots.borrow_mut();
+            let node = match TreeNode::find(&mut roots, path) {
+                Some(node) if node.expandable => node,
+                _ => return false,
+            };
+            if node.expanded == expanded {
+                return false;
+            }
+            if expanded && node.children.is_none() {
+                let children = self
+                    .source
+                    .children(&node.node)
+                    .into_iter()
+                    .map(|child| TreeNode::new(&self.source, child))
+                    .collect();
+                node.children = Some(children);
+            }
+            node.expanded = expanded;
+        }
+        self.rebuild();
+        // Keep selection, moving to the collapsed node if it was hidden
+        if let Some(mut sel) = selected {
+            loop {
+                if let Some(row) = self.row_of(&sel) {
+                    self.selected.set(Some(row));
+                    break;
+                }
+                sel.pop();
+            }
+        }
+        true
+    }
+
+    /// Disable the tree view
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the tree view
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Rebuild visible rows
+    fn rebuild(&self) {
+        let mut rows = vec![];
+        let roots = self.roots.borrow();
+        flatten(&roots, &mut vec![], "", self.outline, &mut rows);
+        *self.rows.borrow_mut() = rows;
+    }
+
+    /// Get the visible row of a node path
+    fn row_of(&self, path: &[usize]) -> Option<usize> {
+        self.rows.borrow().iter().position(|r| r.path == path)
+    }
+
+    /// Get the path of a visible row
+    fn row_path(&self, row: usize) -> Vec<usize> {
+        self.rows.borrow()[row].path.clone()
+    }
+
+    /// Get the range of visible rows for a scroll offset
+    fn visible(&self, offset: Pos, height: u16) -> Range<usize> {
+        self.scroll.visible(
+            offset,
+            height,
+            self.rows.borrow().len(),
+            self.selected.get(),
+            usize::from,
+        )
+    }
+
+    /// Select a row
+    fn select(&self, row: usize) -> Option<Action> {
+        if self.selected.get() == Some(row) {
+            return None;
+        }
+        self.selected.set(Some(row));
+        Some(Action::NodeSelected(self.row_path(row)))
+    }
+
+    /// Toggle expansion of a row
+    fn toggle(&self, row: usize) -> Option<Action> {
+        let path = self.row_path(row);
+        let expanded = self.is_expanded(&path);
+        if self.set_expanded(&path, !expanded) {
+            Some(Action::Redraw())
+        } else {
+            None
+        }
+    }
+
+    /// Get the expander and label of a node
+    fn node_text(&self, path: &[usize]) -> (char, String) {
+        let mut roots = self.roots.borrow_mut();
+        match TreeNode::find(&mut roots, path) {
+            Some(node) => {
+                let expander = match (node.expandable, node.expanded) {
+                    (false, _) => ' ',
+                    (true, false) => '▸',
+                    (true, true) => '▾',
+                };
+                (expander, self.source.label(&node.node).into_owned())
+            }
+            None => (' ', String::new()),
+        }
+    }
+
+    /// Handle a navigation key
+    fn nav_key(&self, nav: NavKey) -> Option<Action> {
+        let len = self.rows.borrow().len();
+        let row = match self.selected.get() {
+            Some(row) => row,
+            None => {
+                return self.select(nav_index(nav, self.scroll.top(), len, 0)?)
+            }
+        };
+        let path = self.row_path(row);
+        match nav {
+            NavKey::Right if self.is_expanded(&path) => {
+                let next = row + 1;
+                if next < len && self.row_path(next).len() > path.len() {
+                    self.select(next)
+                } else {
+                    None
+                }
+            }
+            NavKey::Right => self.toggle(row),
+            NavKey::Left if self.is_expanded(&path) => self.toggle(row),
+            NavKey::Left => {
+                let parent = self.row_of(&path[..path.len() - 1])?;
+                self.select(parent)
+            }
+            _ => {
+                let page = usize::from(self.scroll.rows());
+                let row = nav_index(nav, row, len, page)?;
+                self.select(row).or(Some(Action::Redraw()))
+            }
+        }
+    }
+}
+
+impl<S: TreeSource> Widget for TreeView<S> {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        list_style_group(&self.button)
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        let rows = self.rows.borrow();
+        let width = rows
+            .iter()
+            .map(|r| r.guides.width() + 2 + self.node_text(&r.path).1.width())
+            .max()
+            .unwrap_or(0);
+        LengthBound::new(width.min(usize::from(u16::MAX)) as u16..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        let rows = self.rows.borrow().len().min(usize::from(u16::MAX));
+        LengthBound::new(rows as u16..)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let style = cells.style(self.style_group());
+        let guide_style = cells.style(StyleGroup::Muted);
+        let selected_style = cells.style(StyleGroup::Focused);
+        let visible = self.visible(offset, cells.height());
+        let top = visible.start;
+        let selected = self.selected.get();
+        for index in visible {
+            let (guides, path) = {
+                let rows = self.rows.borrow();
+                (rows[index].guides.clone(), rows[index].path.clone())
+            };
+            let (expander, label) = self.node_text(&path);
+            cells.move_to(0, (index - top) as u16)?;
+            cells.set_style(guide_style)?;
+            cells.print_str(&guides)?;
+            cells.set_style(style)?;
+            cells.print_char(expander)?;
+            cells.print_char(' ')?;
+            if selected == Some(index) {
+                cells.set_style(selected_style)?;
+            }
+            cells.print_str(&label)?;
+            let used = guides.width() + 2 + label.width();
+            if selected == Some(index) {
+                fill_row(cells, used)?;
+            }
+        }
+        cells.set_style(style)
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        let redraw = self.button.mouse_event(mev);
+        match mev {
+            MouseEvent::ButtonDown(MouseButton::Left)
+                if self.button.is_focused() =>
+            {
+                let row = self.scroll.row_at(pos);
+                let expander = match self.rows.borrow().get(row) {
+                    Some(r) => r.guides.width(),
+                    None => return redraw,
+                };
+                if usize::from(pos.col) == expander {
+                    self.toggle(row).or(redraw)
+                } else {
+                    self.select(row).or(redraw)
+                }
+            }
+            _ => redraw,
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if !self.button.is_focused() {
+            return None;
+        }
+        match (key, mods) {
+            (KeyPress::Navigation(NavKey::Enter), ModKeys::Empty)
+            | (KeyPress::Character(' '), ModKeys::Empty) => {
+                self.selected.get().and_then(|row| self.toggle(row))
+            }
+            (KeyPress::Navigation(nav), ModKeys::Empty) => self.nav_key(nav),
+            _ => None,
+        }
+    }
+
+    /// Get the row of the selected node
+    fn cursor(&self) -> Option<Pos> {
+        self.selected
+            .get()
+            .map(|row| Pos::new(0, row.min(usize::from(u16::MAX)) as u16))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Source with numbered nodes, counting loads
+    struct Numbers {
+        loads: Cell<usize>,
+    }
+
+    impl TreeSource for Numbers {
+        type Node = String;
+
+        fn roots(&self) -> Vec<String> {
+            vec!["1".into(), "2".into()]
+        }
+
+        fn label(&self, node: &String) -> Cow<'_, str> {
+            Cow::Owned(node.clone())
+        }
+
+        fn has_children(&self, node: &String) -> bool {
+            node.len() < 3
+        }
+
+        fn children(&self, node: &String) -> Vec<String> {
+            self.loads.set(self.loads.get() + 1);
+            (1..=2).map(|i| format!("{}{}", node, i)).collect()
+        }
+    }
+
+    fn tree() -> TreeView<Numbers> {
+        let tree = TreeView::new(Numbers {
+            loads: Cell::new(0),
+        });
+        tree.focus(FocusEvent::Offer);
+        tree
+    }
+
+    #[test]
+    fn guides() {
+        let tree = tree();
+        assert_eq!(tree.rows.borrow().len(), 2);
+        tree.set_selected(&[0, 0, 1]);
+        assert_eq!(tree.source().loads.get(), 2);
+        let guides: Vec<_> = tree
+            .rows
+            .borrow()
+            .iter()
+            .map(|r| r.guides.clone())
+            .collect();
+        assert_eq!(guides, vec!["", "├─", "│ ├─", "│ └─", "└─", ""]);
+        assert_eq!(tree.node_text(&[0, 0, 1]), (' ', "112".into()));
+        assert_eq!(tree.selected(), Some(vec![0, 0, 1]));
+        assert!(tree.set_expanded(&[0], false));
+        assert_eq!(tree.selected(), Some(vec![0]));
+        assert!(tree.set_expanded(&[0], true));
+        assert_eq!(tree.source().loads.get(), 2);
+        assert_eq!(tree.rows.borrow().len(), 6);
+    }
+
+    #[test]
+    fn keys() {
+        let tree = tree();
+        let key =
+            |nav| tree.key_event(KeyPress::Navigation(nav), ModKeys::Empty);
+        assert_eq!(key(NavKey::Home), Some(Action::NodeSelected(vec![0])));
+        assert_eq!(key(NavKey::Right), Some(Action::Redraw()));
+        assert_eq!(key(NavKey::Right), Some(Action::NodeSelected(vec![0, 0])));
+        assert_eq!(key(NavKey::End), Some(Action::NodeSelected(vec![1])));
+        assert_eq!(key(NavKey::Up), Some(Action::NodeSelected(vec![0, 1])));
+        assert_eq!(key(NavKey::Left), Some(Action::NodeSelected(vec![0])));
+        assert_eq!(key(NavKey::Left), Some(Action::Redraw()));
+        assert_eq!(tree.rows.borrow().len(), 2);
+        assert_eq!(key(NavKey::Enter), Some(Action::Redraw()));
+        assert!(tree.is_expanded(&[0]));
+    }
+
+    #[test]
+    fn mouse() {
+        let tree = tree();
+        let down = MouseEvent::ButtonDown(MouseButton::Left);
+        let dim = Dim::new(10, 10);
+        let mev = |col, row| {
+            tree.mouse_event(down, ModKeys::Empty, dim, Pos::new(col, row))
+        };
+        assert_eq!(mev(0, 1), Some(Action::Redraw()));
+        assert!(tree.is_expanded(&[1]));
+        assert_eq!(mev(3, 2), Some(Action::NodeSelected(vec![1, 0])));
+        assert_eq!(mev(2, 3), Some(Action::Redraw()));
+        assert!(tree.is_expanded(&[1, 1]));
+        assert_eq!(mev(5, 9), None);
+    }
+}
@@ -0,0 +1,306 @@
+// virtuallist.rs
+//
+// Copyright (c) 2021  Douglas P Lau
+//
+use crate::input::{
+    Action, FocusEvent, KeyPress, ModKeys, MouseButton, MouseEvent,
+};
+use crate::layout::{Cells, Dim, LengthBound, Pos};
+use crate::text::{StyleGroup, Theme};
+use crate::widget::{
+    fill_row, list_style_group, nav_index, ButtonState, ListSource, RowScroll,
+};
+use crate::{Result, Widget};
+use std::cell::Cell;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Maximum number of rows which can be scrolled exactly
+const MAX_ROWS: usize = u16::MAX as usize;
+
+/// Virtual list widget, for very large item sources
+///
+/// Only visible items are requested from the [ListSource], and no widgets
+/// are created for items.  When wrapped in a [ScrollView], the scroll bar
+/// reflects the total number of items.  Sources with more than 65535 items
+/// are scrolled proportionally, since scroll offsets are limited to `u16`.
+///
+/// While focused, `Up` / `Down`, `PageUp` / `PageDown` and `Home` / `End`
+/// move the selection, producing `Action::Selected`.  The selected item is
+/// drawn with the `StyleGroup::Focused` style.
+///
+/// [ListSource]: trait.ListSource.html
+/// [ScrollView]: struct.ScrollView.html
+pub struct VirtualList<S: ListSource> {
+    /// Item source
+    source: S,
+    /// Button state
+    button: ButtonState,
+    /// Selected item index
+    selected: Cell<Option<usize>>,
+    /// Row scrolling state
+    scroll: RowScroll,
+}
+
+impl<S: ListSource> VirtualList<S> {
+    /// Create a new virtual list widget
+    pub fn new(source: S) -> Self {
+        let button = ButtonState::new();
+        let selected = Cell::new(None);
+        let scroll = RowScroll::default();
+        Self {
+            source,
+            button,
+            selected,
+            scroll,
+        }
+    }
+
+    /// Get the item source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get the selected item index
+    pub fn selected(&self) -> Option<usize> {
+        self.selected.get()
+    }
+
+    /// Select an item
+    pub fn set_selected(&self, index: Option<usize>) {
+        self.selected.set(index.filter(|i| *i < self.source.len()));
+    }
+
+    /// Disable the list
+    pub fn disable(&self) {
+        self.button.disable();
+    }
+
+    /// Enable the list
+    pub fn enable(&self) {
+        self.button.enable();
+    }
+
+    /// Get the number of virtual (scrollable) rows
+    fn virtual_rows(&self) -> u16 {
+        self.source.len().min(MAX_ROWS) as u16
+    }
+
+    /// Get the item and virtual row spans, for proportional scrolling
+    ///
+    /// Returns `None` when rows map directly to items.
+    fn spans(&self, rows: u16) -> Option<(u128, u128)> {
+        let len = self.source.len();
+        let rows = usize::from(rows);
+        let vrows = usize::from(self.virtual_rows());
+        if len > MAX_ROWS && vrows > rows {
+            Some(((len - rows) as u128, (vrows - rows) as u128))
+        } else {
+            None
+        }
+    }
+
+    /// Get the index of the item at the top of a virtual row
+    fn row_index(&self, row: u16, rows: u16) -> usize {
+        match self.spans(rows) {
+            Some((span, vspan)) => (u128::from(row) * span / vspan) as usize,
+            None => usize::from(row),
+        }
+    }
+
+    /// Get the virtual row of an item
+    fn index_row(&self, index: usize, rows: u16) -> u16 {
+        match self.spans(rows) {
+            Some((span, vspan)) => {
+                (index as u128 * vspan / span).min(vspan) as u16
+            }
+            None => index.min(MAX_ROWS) as u16,
+        }
+    }
+
+    /// Get the range of visible items for a scroll offset
+    fn visible(&self, offset: Pos, rows: u16) -> Range<usize> {
+        self.scroll.visible(
+            offset,
+            rows,
+            self.source.len(),
+            self.selected.get(),
+            |row| self.row_index(row, rows),
+        )
+    }
+
+    /// Select an item
+    fn select(&self, index: usize) -> Option<Action> {
+        if self.selected.get() == Some(index) {
+            return None;
+        }
+        self.selected.set(Some(index));
+        Some(Action::Selected(index))
+    }
+}
+
+impl<S: ListSource> Widget for VirtualList<S> {
+    /// Get the style group
+    fn style_group(&self) -> StyleGroup {
+        list_style_group(&self.button)
+    }
+
+    /// Get the width bounds
+    fn width_bounds(&self, _theme: &Theme) -> LengthBound {
+        LengthBound::new(1..)
+    }
+
+    /// Get the height bounds
+    fn height_bounds(&self, _theme: &Theme, _width: u16) -> LengthBound {
+        LengthBound::new(self.virtual_rows()..)
+    }
+
+    /// Draw the widget
+    fn draw(&self, cells: &mut Cells, offset: Pos) -> Result<()> {
+        let style = cells.style(self.style_group());
+        let selected_style = cells.style(StyleGroup::Focused);
+        let selected = self.selected.get();
+        let visible = self.visible(offset, cells.height());
+        let top = visible.start;
+        for index in visible {
+            let row = (index - top) as u16;
+            let item = self.source.item(index);
+            if selected == Some(index) {
+                cells.set_style(selected_style)?;
+            } else {
+                cells.set_style(style)?;
+            }
+            cells.move_to(0, row)?;
+            cells.print_str(&item)?;
+            fill_row(cells, item.width())?;
+        }
+        cells.set_style(style)
+    }
+
+    /// Handle focus event
+    fn focus(&self, fev: FocusEvent) -> Option<Action> {
+        self.button.focus(fev)
+    }
+
+    /// Handle mouse events
+    fn mouse_event(
+        &self,
+        mev: MouseEvent,
+        _mods: ModKeys,
+        _dim: Dim,
+        pos: Pos,
+    ) -> Option<Action> {
+        let redraw = self.button.mouse_event(mev);
+        let len = self.source.len();
+        let top = self.scroll.top();
+        match mev {
+            MouseEvent::ButtonDown(MouseButton::Left)
+                if self.button.is_focused() =>
+            {
+                let index = self.scroll.row_at(pos);
+                if index < len {
+                    self.select(index).or(redraw)
+                } else {
+                    redraw
+                }
+            }
+            // Only received when not wrapped in a scroll view
+            MouseEvent::ScrollUp() if top > 0 => {
+                self.scroll.set_top(top - 1);
+                Some(Action::Redraw())
+            }
+            MouseEvent::ScrollDown() if top + 1 < len => {
+                self.scroll.set_top(top + 1);
+                Some(Action::Redraw())
+            }
+            _ => redraw,
+        }
+    }
+
+    /// Handle key events
+    fn key_event(&self, key: KeyPress, mods: ModKeys) -> Option<Action> {
+        if !self.button.is_focused() {
+            return None;
+        }
+        match (key, mods) {
+            (KeyPress::Navigation(nav), ModKeys::Empty) => {
+                let len = self.source.len();
+                let page = usize::from(self.scroll.rows());
+                let index = match self.selected.get() {
+                    Some(sel) => nav_index(nav, sel, len, page)?,
+                    None => nav_index(nav, self.scroll.top(), len, 0)?,
+                };
+                self.select(index).or(Some(Action::Redraw()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the virtual row of the selected item
+    fn cursor(&self) -> Option<Pos> {
+        let rows = self.scroll.rows();
+        self.selected
+            .get()
+            .map(|sel| Pos::new(0, self.index_row(sel, rows)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::NavKey;
+    use std::borrow::Cow;
+
+    /// Source with many generated items
+    struct Numbers(usize);
+
+    impl ListSource for Numbers {
+        fn len(&self) -> usize {
+            self.0
+        }
+
+        fn item(&self, index: usize) -> Cow<'_, str> {
+            Cow::Owned(index.to_string())
+        }
+    }
+
+    #[test]
+    fn exact() {
+        let list = VirtualList::new(Numbers(100));
+        assert_eq!(list.virtual_rows(), 100);
+        assert_eq!(list.visible(Pos::new(0, 10), 5), 10..15);
+        assert_eq!(list.visible(Pos::new(0, 98), 5), 95..100);
+        list.focus(FocusEvent::Offer);
+        let key =
+            |nav| list.key_event(KeyPress::Navigation(nav), ModKeys::Empty);
+        assert_eq!(key(NavKey::Down), Some(Action::Selected(96)));
+        assert_eq!(key(NavKey::Home), Some(Action::Selected(0)));
+        assert_eq!(list.cursor(), Some(Pos::new(0, 0)));
+        assert_eq!(list.visible(Pos::new(0, 95), 5), 0..5);
+        assert_eq!(key(NavKey::PageDown), Some(Action::Selected(5)));
+        assert_eq!(list.visible(Pos::new(0, 95), 5), 1..6);
+    }
+
+    #[test]
+    fn proportional() {
+        let len = 10_000_000;
+        let list = VirtualList::new(Numbers(len));
+        assert_eq!(list.virtual_rows(), u16::MAX);
+        assert_eq!(list.visible(Pos::new(0, 0), 10), 0..10);
+        let last = u16::MAX - 10;
+        assert_eq!(list.visible(Pos::new(0, last), 10), len - 10..len);
+        list.focus(FocusEvent::Offer);
+        let key =
+            |nav| list.key_event(KeyPress::Navigation(nav), ModKeys::Empty);
+        assert_eq!(key(NavKey::End), Some(Action::Selected(len - 1)));
+        assert_eq!(key(NavKey::Up), Some(Action::Selected(len - 2)));
+        assert_eq!(list.cursor(), Some(Pos::new(0, last)));
+        assert_eq!(list.visible(Pos::new(0, last), 10), len - 10..len);
+        let click = MouseEvent::ButtonDown(MouseButton::Left);
+        let pos = Pos::new(0, last + 3);
+        assert_eq!(
+            list.mouse_event(click, ModKeys::Empty, Dim::new(10, 10), pos),
+            Some(Action::Selected(len - 7))
+        );
+    }
+}